@@ -11,8 +11,7 @@
 //! `maybe-async` help unifying async and sync implementation by **procedural
 //! macro**.
 //! - Write async code with normal `async`, `await`, and let `maybe_async`
-//!   handles
-//! those `async` and `await` when you need a blocking code.
+//!   handles those `async` and `await` when you need a blocking code.
 //! - Switch between sync and async by toggling `is_sync` feature gate in
 //!   `Cargo.toml`.
 //! - use `must_be_async` and `must_be_sync` to keep code in specified version
@@ -24,7 +23,10 @@
 //! - trait item declaration
 //! - trait implementation
 //! - function definition
-//! - struct definition
+//! - struct and enum definition, and type alias, rewriting their future types
+//!   and dropping the `Future` bounds of their generic types
+//! - `use` declaration, dropping or remapping async only imports
+//! - inline module, converting every item inside it
 //!
 //! **RECOMMENDATION**: Enable **resolver ver2** in your crate, which is
 //! introduced in Rust 1.51. If not, two crates in dependency with conflict
@@ -118,6 +120,87 @@
 //!     **Convert to sync code**. Convert the async code into sync code by
 //!     removing all `async move`, `async` and `await` keyword
 //!
+//!     Nested items, like an `async fn` or an `impl` block declared inside a
+//!     function body, are converted as well. Mark a nested item with
+//!     `#[maybe_async::must_be_async]` to keep it async.
+//!
+//!     Future constructors that are identity in blocking code are unwrapped:
+//!     `ready(v)` of `std::future` or `futures` and `pin!(fut)` of `std::pin`
+//!     become `v` and `fut`, `ok(v)` and `err(e)` of `futures` become `Ok(v)`
+//!     and `Err(e)`, and `block_on(fut)` of `futures::executor` becomes `fut`.
+//!     They are recognised by their full path, like
+//!     `futures::future::ready(v)`, or when the converted item imports them, so
+//!     a function of your own named `ready` is left alone.
+//!
+//!     Concurrent joins run one after the other, from left to right:
+//!     `join!(a, b)` becomes `(a, b)`, `try_join!(a, b)` becomes `Ok((a, b))`
+//!     or the first error, and `join_all(iter)` and `try_join_all(iter)` collect
+//!     `iter` into a `Vec<_>` and a `Result<Vec<_>, _>`. Like future constructors,
+//!     the macros of `futures` and `tokio` and the functions of `futures` are
+//!     recognised by their full path or an import in the converted item.
+//!
+//!     Constructs with no sync equivalent, `select!` of `futures` and `tokio`,
+//!     `poll_fn` and manual polling with `task::Poll` and `task::Context`, are
+//!     reported as compile errors, write both versions with `sync_impl` and
+//!     `async_impl` for them. Like future constructors, they are recognised by
+//!     their full path or an import in the converted item.
+//!
+//!     A hand written `impl Future` or `impl IntoFuture` block has no sync
+//!     equivalent either and is dropped, unless the `wait` option below is set.
+//!
+//!     In a `use` declaration, imports only used by async code, like `Future`,
+//!     `Pin`, `FutureExt` or `StreamExt` of `std`, `futures` or `tokio`, are
+//!     dropped once nothing in the converted item refers to them anymore. They
+//!     are kept for sync code using them, a nested `must_be_async` item or a
+//!     hand written future kept by the `wait` option. The IO traits
+//!     `AsyncReadExt`, `AsyncWriteExt`, `AsyncBufReadExt` and `AsyncSeekExt` of
+//!     `tokio`, `futures` or `async_std` are imported as `std::io::Read`,
+//!     `Write`, `BufRead` and `Seek`, and the `fs`, `io` and `net` modules of
+//!     `tokio` and `async_std` as those of `std`, so `use tokio::fs::File`
+//!     becomes `use std::fs::File`. `sleep` of `tokio::time` and
+//!     `async_std::task` becomes `std::thread::sleep`.
+//!
+//!     Options of the sync conversion can be passed to `maybe_async` and
+//!     `must_be_sync`, after the async trait mode if any, e.g.
+//!     `#[maybe_async(?Send, lazy)]`:
+//!     - `lazy`: an async block bound to a variable, `let fut = async { .. }`,
+//!       becomes a closure and `fut.await` becomes `fut()`, so the block
+//!       still runs where it is awaited rather than where it is defined.
+//!       The variable can be moved into another one with `let`, and a type
+//!       annotation of the future becomes the return type of the closure. Any
+//!       other async block that is not awaited in place, returned or spawned,
+//!       like one pushed into a `Vec`, passed as an argument or stored in a
+//!       struct field, would run where it is defined and is reported as a
+//!       compile error, write both versions with `sync_impl` and `async_impl`
+//!       for it.
+//!     - `spawn_thread`: a task spawned on `tokio`, `async_std` or `smol` runs
+//!       in a thread, `tokio::spawn(async move { .. })` becomes
+//!       `std::thread::spawn(move || { .. })`, and awaiting its `JoinHandle`
+//!       becomes `join()`, a `Result` for `tokio` whose error is the panic of the
+//!       thread, and the output of the thread, resuming its panic, otherwise.
+//!       `spawn_blocking(f).await` becomes `f()`, in an `Ok` for `tokio`.
+//!       Like future constructors, `spawn` and `spawn_blocking` are recognised by
+//!       their full path, like `tokio::task::spawn`, or an import in the converted
+//!       item.
+//!       Only a `JoinHandle` awaited directly, `spawn(..).await`, or through the
+//!       variable it is bound to with `let` is joined. A handle moved into a
+//!       collection, like a `Vec`, is not, join it in code written with
+//!       `sync_impl` and `async_impl`.
+//!     - `wait`: a hand written `impl Future` or `impl IntoFuture` block, which
+//!       is otherwise dropped, is kept and the type gets a `fn wait(self)`
+//!       method, blocking the thread until the future is ready.
+//!     - `future_alias = "ApiFuture"`: a crate-local alias of a future type,
+//!       like `type ApiFuture<'a, T> = BoxFuture<'a, T>`, is rewritten to its
+//!       first type argument, so `-> ApiFuture<'_, T>` becomes `-> T`.
+//!       `BoxFuture` and `LocalBoxFuture` of `futures` are always recognised.
+//!       Can be repeated for several aliases.
+//!     - `future_adapter = "retry"`: a method that only adapts a future is
+//!       removed when directly awaited, so `fut.retry().await` becomes `fut`.
+//!       `boxed`, `boxed_local`, `fuse` and `into_future` of `futures`,
+//!       `instrument`, `in_current_span`, `with_subscriber` and
+//!       `with_current_subscriber` of `tracing`, and `Box::pin(fut).await`
+//!       are always removed. Can be repeated for several methods.
+//!
 //!
 //! - `sync_impl`
 //!
@@ -142,6 +225,46 @@
 //!     - `#[async_impl(?Send)]`
 //!     - `#[async_impl(AFIT)]`
 //!
+//! - `maybe_async_items`, `must_be_async_items` and `must_be_sync_items`
+//!
+//!     Function-like counterparts of `maybe_async`, `must_be_async` and
+//!     `must_be_sync`, converting any number of items at once, including
+//!     `const`, `use` and `macro_rules!` items, whose `.await` are removed in
+//!     sync mode. Arguments of the attributes come first, in parentheses:
+//!
+//!     ```rust
+//!     maybe_async::maybe_async_items! {
+//!         (?Send, lazy)
+//!
+//!         const RETRIES: usize = 3;
+//!
+//!         macro_rules! fetch_all {
+//!             ($($fut:expr),*) => { ($($fut.await,)*) };
+//!         }
+//!
+//!         async fn fetch() -> u32 { 1 }
+//!     }
+//!     ```
+//!
+//! - `include_file`
+//!
+//!     Include the items of a source file, converted like `maybe_async_items`,
+//!     so that one file provides both an async and a blocking client. The path
+//!     is relative to `CARGO_MANIFEST_DIR`, and may be preceded by the
+//!     conversion: `maybe`, the default, `sync` or `async`, along with the
+//!     arguments of the attributes in parentheses. The crate is rebuilt when
+//!     the file changes.
+//!
+//!     ```rust, ignore
+//!     mod r#async {
+//!         maybe_async::include_file!(async(?Send), "src/client.rs");
+//!     }
+//!
+//!     mod blocking {
+//!         maybe_async::include_file!(sync, "src/client.rs");
+//!     }
+//!     ```
+//!
 //! - `test`
 //!
 //!     Handy macro to unify async and sync **unit and e2e test** code.
@@ -297,6 +420,7 @@
 //!
 //! # License
 //! MIT
+#![allow(clippy::doc_overindented_list_items)]
 
 extern crate proc_macro;
 
@@ -307,125 +431,391 @@ use syn::{
     ext::IdentExt,
     parenthesized,
     parse::{ParseStream, Parser},
-    parse_macro_input, token, Ident, ImplItem, LitStr, Meta, Result, Token, TraitItem,
+    parse_macro_input, parse_quote, token, Attribute, Ident, ItemMod, LitStr, Meta, Result, Token,
 };
 
 use quote::quote;
 
-use crate::{parse::Item, visit::AsyncAwaitRemoval};
+use crate::{
+    parse::Item,
+    visit::{has_maybe_async_attr, is_future_impl, AsyncAwaitRemoval, SyncOptions},
+};
 
 mod parse;
 mod visit;
+#[derive(Clone, Copy, Default)]
 enum AsyncTraitMode {
+    #[default]
     Send,
     NotSend,
     Off,
 }
 
+fn async_trait_attr(async_trait_mode: AsyncTraitMode) -> Option<Attribute> {
+    match async_trait_mode {
+        AsyncTraitMode::Send => Some(parse_quote!(#[async_trait::async_trait])),
+        AsyncTraitMode::NotSend => Some(parse_quote!(#[async_trait::async_trait(?Send)])),
+        AsyncTraitMode::Off => None,
+    }
+}
+
 fn convert_async(input: &mut Item, async_trait_mode: AsyncTraitMode) -> TokenStream2 {
     match input {
-        Item::Trait(item) => match async_trait_mode {
-            AsyncTraitMode::Send => quote!(#[async_trait::async_trait]#item),
-            AsyncTraitMode::NotSend => quote!(#[async_trait::async_trait(?Send)]#item),
-            AsyncTraitMode::Off => quote!(#item),
-        },
+        Item::Trait(item) => {
+            let attr = async_trait_attr(async_trait_mode);
+            quote!(#attr #item)
+        }
         Item::Impl(item) => {
-            let async_trait_mode = item
+            let attr = item
                 .trait_
                 .as_ref()
-                .map_or(AsyncTraitMode::Off, |_| async_trait_mode);
-            match async_trait_mode {
-                AsyncTraitMode::Send => quote!(#[async_trait::async_trait]#item),
-                AsyncTraitMode::NotSend => quote!(#[async_trait::async_trait(?Send)]#item),
-                AsyncTraitMode::Off => quote!(#item),
-            }
+                .filter(|_| !is_future_impl(item))
+                .and_then(|_| async_trait_attr(async_trait_mode));
+            quote!(#attr #item)
         }
         Item::Fn(item) => quote!(#item),
         Item::Static(item) => quote!(#item),
+        Item::Struct(item) => quote!(#item),
+        Item::Enum(item) => quote!(#item),
+        Item::Type(item) => quote!(#item),
+        Item::Use(item) => quote!(#item),
+        Item::Mod(item) => {
+            convert_async_mod(item, async_trait_mode);
+            quote!(#item)
+        }
     }
 }
 
-fn convert_sync(input: &mut Item) -> TokenStream2 {
-    match input {
-        Item::Impl(item) => {
-            for inner in &mut item.items {
-                if let ImplItem::Fn(ref mut method) = inner {
-                    if method.sig.asyncness.is_some() {
-                        method.sig.asyncness = None;
-                    }
-                }
+/// Add `async_trait` to every trait and trait impl inside the module,
+/// recursively.
+fn convert_async_mod(item: &mut ItemMod, async_trait_mode: AsyncTraitMode) {
+    if let Some((_, items)) = &mut item.content {
+        add_async_trait(items, async_trait_mode);
+    }
+}
+
+/// Add `async_trait` to every trait and trait impl of `items`, recursively.
+/// Items marked by another `maybe_async` attribute are left to that attribute.
+fn add_async_trait(items: &mut [syn::Item], async_trait_mode: AsyncTraitMode) {
+    for inner in items {
+        match inner {
+            syn::Item::Trait(item) if !has_maybe_async_attr(&item.attrs) => {
+                item.attrs.splice(0..0, async_trait_attr(async_trait_mode));
             }
-            AsyncAwaitRemoval.remove_async_await(quote!(#item))
-        }
-        Item::Trait(item) => {
-            for inner in &mut item.items {
-                if let TraitItem::Fn(ref mut method) = inner {
-                    if method.sig.asyncness.is_some() {
-                        method.sig.asyncness = None;
-                    }
-                }
+            syn::Item::Impl(item)
+                if item.trait_.is_some()
+                    && !is_future_impl(item)
+                    && !has_maybe_async_attr(&item.attrs) =>
+            {
+                item.attrs.splice(0..0, async_trait_attr(async_trait_mode));
+            }
+            syn::Item::Mod(item) if !has_maybe_async_attr(&item.attrs) => {
+                convert_async_mod(item, async_trait_mode)
             }
-            AsyncAwaitRemoval.remove_async_await(quote!(#item))
+            _ => {}
         }
-        Item::Fn(item) => {
-            if item.sig.asyncness.is_some() {
-                item.sig.asyncness = None;
+    }
+}
+
+fn convert_sync(input: &mut Item, options: &SyncOptions) -> TokenStream2 {
+    let mut visitor = AsyncAwaitRemoval::new(options);
+    match input {
+        Item::Impl(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Trait(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Fn(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Static(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Mod(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Struct(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Enum(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Type(item) => visitor.remove_async_await(quote!(#item)),
+        Item::Use(item) => visitor.remove_async_await(quote!(#item)),
+    }
+}
+
+/// Input of the function-like macros: arguments in parentheses, if any,
+/// followed by any number of items, e.g.
+/// `maybe_async_items! { (?Send, lazy) fn a() {} trait B {} }`.
+struct Items {
+    args: Args,
+    items: Vec<syn::Item>,
+}
+
+fn parse_items(input: ParseStream) -> Result<Items> {
+    let args = if input.peek(token::Paren) {
+        let args;
+        parenthesized!(args in input);
+        args.call(parse_args)?
+    } else {
+        Args::default()
+    };
+    let mut items = vec![];
+    while !input.is_empty() {
+        items.push(input.parse()?);
+    }
+    Ok(Items { args, items })
+}
+
+fn convert_items_sync(items: &Items) -> TokenStream2 {
+    let inner = &items.items;
+    AsyncAwaitRemoval::new(&items.args.sync).remove_async_await(quote!(#(#inner)*))
+}
+
+fn convert_items_async(items: &mut Items) -> TokenStream2 {
+    add_async_trait(&mut items.items, items.args.mode);
+    let inner = &items.items;
+    quote!(#(#inner)*)
+}
+
+/// Conversion of `include_file`, following the `is_sync` feature like
+/// `maybe_async`, or always to sync or async code.
+enum Conversion {
+    Maybe,
+    Sync,
+    Async,
+}
+
+/// Input of `include_file`: the conversion and its arguments, if any, followed
+/// by the path of the file, e.g. `include_file!(async(?Send), "src/client.rs")`.
+struct IncludeFile {
+    conversion: Conversion,
+    args: Args,
+    path: LitStr,
+}
+
+fn parse_include_file(input: ParseStream) -> Result<IncludeFile> {
+    let mut conversion = Conversion::Maybe;
+    let mut args = Args::default();
+    if !input.peek(LitStr) {
+        let name = input.call(Ident::parse_any)?;
+        conversion = match name.to_string().as_str() {
+            "maybe" => Conversion::Maybe,
+            "sync" => Conversion::Sync,
+            "async" => Conversion::Async,
+            _ => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "Only accepts `maybe`, `sync` or `async`, followed by the path of the file",
+                ))
             }
-            AsyncAwaitRemoval.remove_async_await(quote!(#item))
+        };
+        if input.peek(token::Paren) {
+            let inner;
+            parenthesized!(inner in input);
+            args = inner.call(parse_args)?;
         }
-        Item::Static(item) => AsyncAwaitRemoval.remove_async_await(quote!(#item)),
+        input.parse::<Token![,]>()?;
     }
+    let path = input.parse()?;
+    input.parse::<Option<Token![,]>>()?;
+    Ok(IncludeFile {
+        conversion,
+        args,
+        path,
+    })
 }
 
-fn async_mode(arg: &str) -> Result<AsyncTraitMode> {
-    match arg {
-        "" | "Send" => Ok(AsyncTraitMode::Send),
-        "?Send" => Ok(AsyncTraitMode::NotSend),
-        // acronym for Async Function in Trait,
-        // TODO make AFIT as default in future release
-        "AFIT" => Ok(AsyncTraitMode::Off),
-        _ => Err(syn::Error::new(
-            Span::call_site(),
-            "Only accepts `Send`, `?Send` or `AFIT` (native async function in trait)",
-        )),
+/// Read and convert the items of the file of `include`, along with an
+/// `include_bytes!` of the file, so that the crate is rebuilt when it changes.
+fn convert_file(include: IncludeFile) -> Result<TokenStream2> {
+    let path = include.path.value();
+    let root = std::env::var("CARGO_MANIFEST_DIR").map_err(|_| {
+        syn::Error::new(
+            include.path.span(),
+            "`CARGO_MANIFEST_DIR` is not set, `include_file` only works with cargo",
+        )
+    })?;
+    let full_path = std::path::Path::new(&root).join(&path);
+    let content = std::fs::read_to_string(&full_path)
+        .map_err(|e| syn::Error::new(include.path.span(), format!("Cannot read `{path}`: {e}")))?;
+    let file = syn::parse_file(&content)
+        .map_err(|e| syn::Error::new(include.path.span(), format!("Cannot parse `{path}`: {e}")))?;
+    if !file.attrs.is_empty() {
+        return Err(syn::Error::new(
+            include.path.span(),
+            format!("Inner attributes of `{path}` are not supported, put them on the module"),
+        ));
+    }
+
+    let mut items = Items {
+        args: include.args,
+        items: file.items,
+    };
+    let is_sync = match include.conversion {
+        Conversion::Maybe => cfg!(feature = "is_sync"),
+        Conversion::Sync => true,
+        Conversion::Async => false,
+    };
+    let converted = if is_sync {
+        convert_items_sync(&items)
+    } else {
+        convert_items_async(&mut items)
+    };
+    let full_path = full_path.to_string_lossy();
+    Ok(quote! {
+        const _: &[u8] = ::core::include_bytes!(#full_path);
+        #converted
+    })
+}
+
+/// Arguments accepted by `maybe_async`, `must_be_async`, `must_be_sync` and
+/// `async_impl`: at most one async trait mode, followed by options of the sync
+/// conversion, e.g. `#[maybe_async(?Send, lazy)]`.
+#[derive(Default)]
+struct Args {
+    mode: AsyncTraitMode,
+    sync: SyncOptions,
+}
+
+fn parse_args(input: ParseStream) -> Result<Args> {
+    let mut mode = None;
+    let mut sync = SyncOptions::default();
+    while !input.is_empty() {
+        let not_send = input.parse::<Option<Token![?]>>()?.is_some();
+        let name = input.call(Ident::parse_any)?;
+        let arg_mode = match name.to_string().as_str() {
+            "Send" if not_send => Some(AsyncTraitMode::NotSend),
+            "Send" => Some(AsyncTraitMode::Send),
+            // acronym for Async Function in Trait,
+            // TODO make AFIT as default in future release
+            "AFIT" if !not_send => Some(AsyncTraitMode::Off),
+            "lazy" if !not_send => {
+                sync.lazy = true;
+                None
+            }
+            "spawn_thread" if !not_send => {
+                sync.spawn_thread = true;
+                None
+            }
+            "wait" if !not_send => {
+                sync.wait = true;
+                None
+            }
+            "future_alias" if !not_send => {
+                input.parse::<Token![=]>()?;
+                let alias = input.parse::<LitStr>()?;
+                sync.future_aliases
+                    .push(alias.parse::<Ident>()?.to_string());
+                None
+            }
+            "future_adapter" if !not_send => {
+                input.parse::<Token![=]>()?;
+                let adapter = input.parse::<LitStr>()?;
+                sync.future_adapters
+                    .push(adapter.parse::<Ident>()?.to_string());
+                None
+            }
+            _ => {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "Only accepts `Send`, `?Send` or `AFIT` (native async function in trait), \
+                     optionally followed by `lazy`, `spawn_thread`, `wait`, \
+                     `future_alias = \"..\"` and `future_adapter = \"..\"`",
+                ))
+            }
+        };
+        if let Some(arg_mode) = arg_mode {
+            if mode.replace(arg_mode).is_some() {
+                return Err(syn::Error::new(
+                    name.span(),
+                    "`Send`, `?Send` and `AFIT` are mutually exclusive",
+                ));
+            }
+        }
+
+        if input.is_empty() {
+            break;
+        }
+        input.parse::<Token![,]>()?;
     }
+    Ok(Args {
+        mode: mode.unwrap_or_default(),
+        sync,
+    })
 }
 
 /// maybe_async attribute macro
 ///
-/// Can be applied to trait item, trait impl, functions and struct impls.
+/// Can be applied to trait item, trait impl, functions, struct impls and
+/// inline modules.
 #[proc_macro_attribute]
 pub fn maybe_async(args: TokenStream, input: TokenStream) -> TokenStream {
-    let mode = match async_mode(args.to_string().replace(" ", "").as_str()) {
-        Ok(m) => m,
+    let args = match parse_args.parse(args) {
+        Ok(args) => args,
         Err(e) => return e.to_compile_error().into(),
     };
     let mut item = parse_macro_input!(input as Item);
 
     let token = if cfg!(feature = "is_sync") {
-        convert_sync(&mut item)
+        convert_sync(&mut item, &args.sync)
     } else {
-        convert_async(&mut item, mode)
+        convert_async(&mut item, args.mode)
     };
     token.into()
 }
 
+/// maybe_async function-like macro
+///
+/// Like the `maybe_async` attribute, but converts any number of items at
+/// once, including `const`, `use` and `macro_rules!` items. Arguments of the
+/// attribute come first, in parentheses.
+#[proc_macro]
+pub fn maybe_async_items(input: TokenStream) -> TokenStream {
+    let mut items = parse_macro_input!(input with parse_items);
+    let token = if cfg!(feature = "is_sync") {
+        convert_items_sync(&items)
+    } else {
+        convert_items_async(&mut items)
+    };
+    token.into()
+}
+
+/// include the items of a file, converted like `maybe_async_items`
+///
+/// The path is relative to `CARGO_MANIFEST_DIR`, and may be preceded by the
+/// conversion, `maybe`, the default, `sync` or `async`, and its arguments, e.g.
+/// `include_file!(async(?Send), "src/client.rs")`.
+#[proc_macro]
+pub fn include_file(input: TokenStream) -> TokenStream {
+    let include = parse_macro_input!(input with parse_include_file);
+    convert_file(include)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+/// convert marked async items to async code with `async-trait`
+#[proc_macro]
+pub fn must_be_async_items(input: TokenStream) -> TokenStream {
+    let mut items = parse_macro_input!(input with parse_items);
+    convert_items_async(&mut items).into()
+}
+
+/// convert marked async items to sync code
+#[proc_macro]
+pub fn must_be_sync_items(input: TokenStream) -> TokenStream {
+    let items = parse_macro_input!(input with parse_items);
+    convert_items_sync(&items).into()
+}
+
 /// convert marked async code to async code with `async-trait`
 #[proc_macro_attribute]
 pub fn must_be_async(args: TokenStream, input: TokenStream) -> TokenStream {
-    let mode = match async_mode(args.to_string().replace(" ", "").as_str()) {
-        Ok(m) => m,
+    let args = match parse_args.parse(args) {
+        Ok(args) => args,
         Err(e) => return e.to_compile_error().into(),
     };
     let mut item = parse_macro_input!(input as Item);
-    convert_async(&mut item, mode).into()
+    convert_async(&mut item, args.mode).into()
 }
 
 /// convert marked async code to sync code
 #[proc_macro_attribute]
-pub fn must_be_sync(_args: TokenStream, input: TokenStream) -> TokenStream {
+pub fn must_be_sync(args: TokenStream, input: TokenStream) -> TokenStream {
+    let args = match parse_args.parse(args) {
+        Ok(args) => args,
+        Err(e) => return e.to_compile_error().into(),
+    };
     let mut item = parse_macro_input!(input as Item);
-    convert_sync(&mut item).into()
+    convert_sync(&mut item, &args.sync).into()
 }
 
 /// mark sync implementation
@@ -449,15 +839,15 @@ pub fn sync_impl(_args: TokenStream, input: TokenStream) -> TokenStream {
 /// When `is_sync` is set, marked code is removed.
 #[proc_macro_attribute]
 pub fn async_impl(args: TokenStream, _input: TokenStream) -> TokenStream {
-    let mode = match async_mode(args.to_string().replace(" ", "").as_str()) {
-        Ok(m) => m,
+    let args = match parse_args.parse(args) {
+        Ok(args) => args,
         Err(e) => return e.to_compile_error().into(),
     };
     let token = if cfg!(feature = "is_sync") {
         quote!()
     } else {
         let mut item = parse_macro_input!(_input as Item);
-        convert_async(&mut item, mode)
+        convert_async(&mut item, args.mode)
     };
     token.into()
 }
@@ -484,8 +874,6 @@ fn parse_nested_meta_or_str(input: ParseStream) -> Result<TokenStream2> {
 /// as async and the lib to run async test, e.x. `async-std::test`,
 /// `tokio::test`, or any valid attribute macro.
 ///
-/// **ATTENTION**: do not write await inside a assert macro
-///
 /// - Examples
 ///
 /// ```rust
@@ -548,6 +936,7 @@ fn parse_nested_meta_or_str(input: ParseStream) -> Result<TokenStream2> {
 ///     assert_eq!(res, true);
 /// }
 /// ```
+#[allow(clippy::test_attr_in_doctest)]
 #[proc_macro_attribute]
 pub fn test(args: TokenStream, input: TokenStream) -> TokenStream {
     match parse_test_cfg.parse(args) {
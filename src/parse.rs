@@ -1,7 +1,8 @@
-use proc_macro2::Span;
+use proc_macro2::TokenTree;
 use syn::{
-    parse::{discouraged::Speculative, Parse, ParseStream, Result},
-    Attribute, Error, ItemFn, ItemImpl, ItemStatic, ItemTrait,
+    parse::{Parse, ParseStream, Result},
+    Attribute, Error, ItemEnum, ItemFn, ItemImpl, ItemMod, ItemStatic, ItemStruct, ItemTrait,
+    ItemType, ItemUse, LitStr, Token, Visibility,
 };
 
 pub enum Item {
@@ -9,35 +10,133 @@ pub enum Item {
     Impl(ItemImpl),
     Fn(ItemFn),
     Static(ItemStatic),
+    Mod(ItemMod),
+    Struct(ItemStruct),
+    Enum(ItemEnum),
+    Type(ItemType),
+    Use(ItemUse),
 }
 
-macro_rules! fork {
-    ($fork:ident = $input:ident) => {{
-        $fork = $input.fork();
-        &$fork
-    }};
+/// Kind of an item, told by its leading keyword.
+enum Kind {
+    Trait,
+    Impl,
+    Fn,
+    Static,
+    Mod,
+    Struct,
+    Enum,
+    Type,
+    Use,
+}
+
+/// Find the kind of the item ahead, skipping its visibility and qualifiers
+/// like `unsafe`, `async` or `extern "C"`. `None` for unsupported items.
+fn peek_kind(input: ParseStream) -> Option<Kind> {
+    let fork = input.fork();
+    fork.parse::<Visibility>().ok()?;
+    loop {
+        let kind = if fork.peek(Token![trait]) {
+            Kind::Trait
+        } else if fork.peek(Token![impl]) {
+            Kind::Impl
+        } else if fork.peek(Token![fn]) {
+            Kind::Fn
+        } else if fork.peek(Token![static]) {
+            Kind::Static
+        } else if fork.peek(Token![mod]) {
+            Kind::Mod
+        } else if fork.peek(Token![struct]) {
+            Kind::Struct
+        } else if fork.peek(Token![enum]) {
+            Kind::Enum
+        } else if fork.peek(Token![type]) {
+            Kind::Type
+        } else if fork.peek(Token![use]) {
+            Kind::Use
+        } else if fork.peek(Token![extern]) {
+            fork.parse::<Token![extern]>().ok()?;
+            fork.parse::<Option<LitStr>>().ok()?;
+            continue;
+        } else if fork.peek(Token![const]) {
+            // `const fn`, rather than a `const` item
+            let is_fn = fork.peek2(Token![fn])
+                || fork.peek2(Token![unsafe])
+                || fork.peek2(Token![async])
+                || fork.peek2(Token![extern]);
+            if !is_fn {
+                return None;
+            }
+            fork.parse::<Token![const]>().ok()?;
+            continue;
+        } else if fork.peek(Token![unsafe])
+            || fork.peek(Token![async])
+            || fork.peek(Token![default])
+            || fork.peek(Token![auto])
+        {
+            fork.parse::<TokenTree>().ok()?;
+            continue;
+        } else {
+            return None;
+        };
+        return Some(kind);
+    }
 }
 
 impl Parse for Item {
     fn parse(input: ParseStream) -> Result<Self> {
         let attrs = input.call(Attribute::parse_outer)?;
-        let mut fork;
-        let item = if let Ok(mut item) = fork!(fork = input).parse::<ItemImpl>() {
-            item.attrs = attrs;
-            Item::Impl(item)
-        } else if let Ok(mut item) = fork!(fork = input).parse::<ItemTrait>() {
-            item.attrs = attrs;
-            Item::Trait(item)
-        } else if let Ok(mut item) = fork!(fork = input).parse::<ItemFn>() {
-            item.attrs = attrs;
-            Item::Fn(item)
-        } else if let Ok(mut item) = fork!(fork = input).parse::<ItemStatic>() {
-            item.attrs = attrs;
-            Item::Static(item)
-        } else {
-            return Err(Error::new(Span::call_site(), "expected impl, trait or fn"));
+        let Some(kind) = peek_kind(input) else {
+            return Err(input.error(
+                "expected one of the supported items: trait, impl, fn, static, inline mod, \
+                 struct, enum, type or use",
+            ));
+        };
+        // parse the item directly, so that a syntax error is reported as is
+        let item = match kind {
+            Kind::Trait => Item::Trait(ItemTrait {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Impl => Item::Impl(ItemImpl {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Fn => Item::Fn(ItemFn {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Static => Item::Static(ItemStatic {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Mod => {
+                let item: ItemMod = input.parse()?;
+                if item.content.is_none() {
+                    return Err(Error::new_spanned(
+                        &item,
+                        "expected inline module with a body, found module declaration",
+                    ));
+                }
+                Item::Mod(ItemMod { attrs, ..item })
+            }
+            Kind::Struct => Item::Struct(ItemStruct {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Enum => Item::Enum(ItemEnum {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Type => Item::Type(ItemType {
+                attrs,
+                ..input.parse()?
+            }),
+            Kind::Use => Item::Use(ItemUse {
+                attrs,
+                ..input.parse()?
+            }),
         };
-        input.advance_to(&fork);
         Ok(item)
     }
 }
@@ -1,116 +1,489 @@
-use proc_macro2::TokenStream;
-use quote::quote;
+use std::{collections::HashSet, mem};
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use quote::{format_ident, quote};
 use syn::{
-    visit_mut::{self, visit_item_mut, visit_path_segment_mut, VisitMut},
-    Expr, ExprBlock, File, GenericArgument, GenericParam, Item, PathArguments, PathSegment,
-    ReturnType, Signature, Stmt, Type, TypeParamBound, WherePredicate,
+    parse::Parser,
+    parse_quote,
+    punctuated::Punctuated,
+    visit::{self, Visit},
+    visit_mut::{self, visit_item_mut, visit_type_mut, VisitMut},
+    Attribute, Block, Error, Expr, ExprBlock, ExprCall, ExprClosure, ExprMacro, ExprPath,
+    ExprReturn, File, GenericArgument, GenericParam, Generics, Ident, ImplItemFn, Item, ItemEnum,
+    ItemFn, ItemImpl, ItemStruct, ItemTrait, ItemUse, Lifetime, Local, Macro, Pat, Path,
+    PathArguments, Signature, Stmt, Token, TraitBound, TraitItemFn, Type, TypeParamBound, TypePath,
+    UseTree, WherePredicate,
 };
 
+/// Attributes of this crate that handle the item they are attached to on their
+/// own.
+const MAYBE_ASYNC_ATTRS: &[&str] = &[
+    "maybe_async",
+    "must_be_async",
+    "must_be_sync",
+    "sync_impl",
+    "async_impl",
+];
+
+/// Whether any of `attrs` is one of the `maybe_async` attributes, matched by
+/// last segment name. `maybe_async::test` is only matched with its full path,
+/// so that plain `#[test]` does not count.
+pub fn has_maybe_async_attr(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        let segs = &attr.path().segments;
+        let Some(last) = segs.last() else {
+            return false;
+        };
+        MAYBE_ASYNC_ATTRS.iter().any(|name| last.ident == name)
+            || (last.ident == "test" && segs.len() == 2 && segs[0].ident == "maybe_async")
+    })
+}
+
+fn item_attrs(item: &Item) -> &[Attribute] {
+    match item {
+        Item::Const(item) => &item.attrs,
+        Item::Enum(item) => &item.attrs,
+        Item::ExternCrate(item) => &item.attrs,
+        Item::Fn(item) => &item.attrs,
+        Item::ForeignMod(item) => &item.attrs,
+        Item::Impl(item) => &item.attrs,
+        Item::Macro(item) => &item.attrs,
+        Item::Mod(item) => &item.attrs,
+        Item::Static(item) => &item.attrs,
+        Item::Struct(item) => &item.attrs,
+        Item::Trait(item) => &item.attrs,
+        Item::TraitAlias(item) => &item.attrs,
+        Item::Type(item) => &item.attrs,
+        Item::Union(item) => &item.attrs,
+        Item::Use(item) => &item.attrs,
+        _ => &[],
+    }
+}
+
 pub struct ReplaceGenericType<'a> {
     generic_type: &'a str,
-    arg_type: &'a PathSegment,
+    arg_type: &'a Type,
 }
 
 impl<'a> ReplaceGenericType<'a> {
-    pub fn new(generic_type: &'a str, arg_type: &'a PathSegment) -> Self {
+    pub fn new(generic_type: &'a str, arg_type: &'a Type) -> Self {
         Self {
             generic_type,
             arg_type,
         }
     }
 
-    pub fn replace_generic_type(item: &mut Item, generic_type: &'a str, arg_type: &'a PathSegment) {
-        let mut s = Self::new(generic_type, arg_type);
-        s.visit_item_mut(item);
+    /// Remove the generic type from generics `<T, F>` and from the where
+    /// clause.
+    pub fn remove_generic_type(&self, generics: &mut Generics) {
+        generics.params = generics
+            .params
+            .iter()
+            .filter(|param| {
+                if let GenericParam::Type(type_param) = param {
+                    !type_param.ident.to_string().eq(self.generic_type)
+                } else {
+                    true
+                }
+            })
+            .cloned()
+            .collect();
+        self.remove_where_predicates(generics);
     }
-}
 
-impl<'a> VisitMut for ReplaceGenericType<'a> {
-    fn visit_item_mut(&mut self, i: &mut Item) {
-        if let Item::Fn(item_fn) = i {
-            // remove generic type from generics <T, F>
-            let args = item_fn
-                .sig
-                .generics
-                .params
+    /// Keep the generic type as a parameter, but remove its bounds from
+    /// generics `<T, F: Future<Output = T>>` and from the where clause. This
+    /// keeps the arity of a trait, struct or enum, so that its impls still name
+    /// it with as many generic arguments.
+    pub fn remove_generic_bounds(&self, generics: &mut Generics) {
+        for param in &mut generics.params {
+            if let GenericParam::Type(type_param) = param {
+                if type_param.ident.to_string().eq(self.generic_type) {
+                    type_param.colon_token = None;
+                    type_param.bounds.clear();
+                }
+            }
+        }
+        self.remove_where_predicates(generics);
+    }
+
+    fn remove_where_predicates(&self, generics: &mut Generics) {
+        if let Some(where_clause) = &mut generics.where_clause {
+            where_clause.predicates = where_clause
+                .predicates
                 .iter()
-                .filter_map(|param| {
-                    if let GenericParam::Type(type_param) = &param {
-                        if type_param.ident.to_string().eq(self.generic_type) {
-                            None
-                        } else {
-                            Some(param)
-                        }
+                .filter(|predicate| {
+                    if let WherePredicate::Type(predicate_type) = predicate {
+                        generic_type_ident(&predicate_type.bounded_ty)
+                            .is_none_or(|ident| !ident.to_string().eq(self.generic_type))
                     } else {
-                        Some(param)
+                        true
                     }
                 })
-                .collect::<Vec<_>>();
-            item_fn.sig.generics.params = args.into_iter().cloned().collect();
-
-            // remove generic type from where clause
-            if let Some(where_clause) = &mut item_fn.sig.generics.where_clause {
-                let new_where_clause = where_clause
-                    .predicates
-                    .iter()
-                    .filter_map(|predicate| {
-                        if let WherePredicate::Type(predicate_type) = predicate {
-                            if let Type::Path(p) = &predicate_type.bounded_ty {
-                                if p.path.segments[0].ident.to_string().eq(self.generic_type) {
-                                    None
-                                } else {
-                                    Some(predicate)
-                                }
-                            } else {
-                                Some(predicate)
-                            }
-                        } else {
-                            Some(predicate)
-                        }
-                    })
-                    .collect::<Vec<_>>();
-
-                where_clause.predicates = new_where_clause.into_iter().cloned().collect();
-            };
+                .cloned()
+                .collect();
         }
-        visit_item_mut(self, i)
     }
-    fn visit_path_segment_mut(&mut self, i: &mut PathSegment) {
-        // replace generic type with target type
-        if i.ident.to_string().eq(&self.generic_type) {
-            *i = self.arg_type.clone();
+}
+
+impl<'a> VisitMut for ReplaceGenericType<'a> {
+    fn visit_type_mut(&mut self, i: &mut Type) {
+        // replace generic type `F`, and its `F::Output`, with target type
+        if let Type::Path(p) = i {
+            let segs = &p.path.segments;
+            let is_generic_type = p.qself.is_none()
+                && p.path.leading_colon.is_none()
+                && segs[0].arguments.is_none()
+                && segs[0].ident.to_string().eq(self.generic_type)
+                && (segs.len() == 1 || (segs.len() == 2 && segs[1].ident == "Output"));
+            if is_generic_type {
+                *i = self.arg_type.clone();
+                return;
+            }
         }
-        visit_path_segment_mut(self, i);
+        visit_type_mut(self, i);
     }
 }
 
-pub struct AsyncAwaitRemoval;
+/// Options of the sync conversion, set by the macro arguments.
+#[derive(Clone, Default)]
+pub struct SyncOptions {
+    /// Turn async blocks bound to a variable into closures and their `.await`
+    /// into a call, so that they still run at the await site. Async blocks that
+    /// would run elsewhere than in async code are reported.
+    pub lazy: bool,
+    /// Run tasks spawned on an async runtime, `tokio::spawn(fut)`, in a thread
+    /// and turn awaiting their `JoinHandle` into `join()`.
+    pub spawn_thread: bool,
+    /// Keep hand written `impl Future` blocks, along with a blocking `wait`
+    /// method, rather than dropping them.
+    pub wait: bool,
+    /// Names of crate-local aliases of future types, like
+    /// `type ApiFuture<'a, T> = BoxFuture<'a, T>`, whose first type argument
+    /// is the output of the future.
+    pub future_aliases: Vec<String>,
+    /// Names of methods that only adapt a future, stripped when they are
+    /// directly awaited, in addition to `FUTURE_ADAPTERS`.
+    pub future_adapters: Vec<String>,
+}
+
+/// Adapter methods of `futures` and `tracing` that are stripped when directly
+/// awaited: `fut.boxed().await` becomes `fut`.
+const FUTURE_ADAPTERS: &[&str] = &[
+    "boxed",
+    "boxed_local",
+    "fuse",
+    "into_future",
+    "instrument",
+    "in_current_span",
+    "with_current_subscriber",
+    "with_subscriber",
+];
+
+/// What a local variable holds, as far as the conversion is concerned.
+#[derive(Clone, Copy, PartialEq)]
+enum Binding {
+    Plain,
+    /// An async block turned into a closure by the `lazy` option.
+    Lazy,
+    /// The `JoinHandle` of a task turned into a thread by the `spawn_thread`
+    /// option, and whether awaiting it gave a `Result`.
+    JoinHandle {
+        result: bool,
+    },
+}
+
+pub struct AsyncAwaitRemoval {
+    options: SyncOptions,
+    /// Local variables in scope, and what they hold. Later bindings shadow
+    /// earlier ones.
+    bindings: Vec<(Ident, Binding)>,
+    /// Imports of the converted item, to resolve the paths it calls.
+    imports: Imports,
+    /// Constructs that cannot be converted, reported after the converted item.
+    errors: Vec<Error>,
+}
 
 impl AsyncAwaitRemoval {
+    pub fn new(options: &SyncOptions) -> Self {
+        Self {
+            options: options.clone(),
+            bindings: vec![],
+            imports: Imports::default(),
+            errors: vec![],
+        }
+    }
+
     pub fn remove_async_await(&mut self, item: TokenStream) -> TokenStream {
         let mut syntax_tree: File = syn::parse(item.into()).unwrap();
+        self.imports = Imports::collect(&syntax_tree);
+        if self.options.lazy {
+            let mut check = LazyCheck {
+                removal: self,
+                errors: vec![],
+            };
+            check.visit_file(&syntax_tree);
+            let errors = check.errors;
+            self.errors.extend(errors);
+        }
         self.visit_file_mut(&mut syntax_tree);
-        quote!(#syntax_tree)
+        // imports are converted last, once it is known which names the
+        // converted item still refers to
+        let references = References::collect(&syntax_tree);
+        SyncUses(&references).visit_file_mut(&mut syntax_tree);
+        let errors = self.errors.drain(..).map(|e| e.to_compile_error());
+        quote!(#syntax_tree #(#errors)*)
+    }
+
+    /// Find generic types bound by `Future<Output = T>`, either in generics
+    /// `<T, F: Future<Output = T>>` or in the where clause, along with `T`.
+    fn search_future_generics(&mut self, generics: &Generics) -> Vec<(String, Type)> {
+        let mut inputs = vec![];
+
+        // generic params: <T:Future<Output=()>, F>
+        for param in &generics.params {
+            // generic param: T:Future<Output=()>
+            if let GenericParam::Type(type_param) = param {
+                if let Some(output) = extract_future_output_from_bounds(type_param.bounds.iter()) {
+                    inputs.push((type_param.ident.to_string(), output));
+                }
+            }
+        }
+
+        if let Some(where_clause) = &generics.where_clause {
+            for predicate in &where_clause.predicates {
+                let WherePredicate::Type(predicate_type) = predicate else {
+                    continue;
+                };
+                let Some(output) = extract_future_output_from_bounds(predicate_type.bounds.iter())
+                else {
+                    // other bounds are kept as is, whatever the bounded type
+                    continue;
+                };
+                if let Some(ident) = generic_type_ident(&predicate_type.bounded_ty) {
+                    inputs.push((ident.to_string(), output));
+                } else {
+                    self.errors.push(Error::new_spanned(
+                        predicate,
+                        "`Future` bound on a type other than a generic parameter cannot be \
+                         converted to sync code, use `sync_impl` and `async_impl` instead",
+                    ));
+                }
+            }
+        }
+        inputs
+    }
+
+    /// Strip the adapters that only make sense on a future from an awaited
+    /// expression, like `fut.boxed()`, `fut.instrument(span)` or
+    /// `Box::pin(fut)`.
+    fn strip_future_adapters(&self, mut expr: Expr) -> Expr {
+        loop {
+            expr = match expr {
+                Expr::MethodCall(call)
+                    if FUTURE_ADAPTERS.iter().any(|name| call.method == name)
+                        || self
+                            .options
+                            .future_adapters
+                            .iter()
+                            .any(|name| call.method == name) =>
+                {
+                    *call.receiver
+                }
+                expr => match unwrap_box_call(&expr) {
+                    Some(inner) => inner,
+                    None => return expr,
+                },
+            }
+        }
+    }
+
+    /// Turn `let fut = async { .. };` into `let fut = || { .. };`, returning
+    /// whether the binding was rewritten. A type annotation of the future,
+    /// `let fut: BoxFuture<'_, T> = ..`, becomes the return type of the
+    /// closure.
+    fn make_lazy(&mut self, local: &mut Local) -> bool {
+        let Some(init) = &mut local.init else {
+            return false;
+        };
+        if init.diverge.is_some() {
+            return false;
+        }
+        let expr = unwrap_box_call_with_async(&init.expr).unwrap_or_else(|| (*init.expr).clone());
+        let Expr::Async(expr) = expr else {
+            return false;
+        };
+        let mut output = None;
+        if let Pat::Type(pat) = &local.pat {
+            if !matches!(*pat.ty, Type::Infer(_)) {
+                let Some(ty) = extract_future_output(&pat.ty, &self.options.future_aliases) else {
+                    self.errors.push(Error::new_spanned(
+                        &pat.ty,
+                        "the output of an async block deferred by `lazy` cannot be told from \
+                         this type, annotate it with a future type like \
+                         `Pin<Box<dyn Future<Output = T>>>` or leave it out",
+                    ));
+                    return false;
+                };
+                output = Some(ty);
+            }
+            local.pat = (*pat.pat).clone();
+        }
+        let capture = &expr.capture;
+        let block = &expr.block;
+        let mut closure: ExprClosure = match output {
+            Some(output) => parse_quote!(#capture || -> #output #block),
+            None => parse_quote!(#capture || #block),
+        };
+        closure.attrs = expr.attrs;
+        *init.expr = Expr::Closure(closure);
+        true
+    }
+
+    fn binding(&self, expr: &Expr) -> Binding {
+        let Expr::Path(path) = expr else {
+            return Binding::Plain;
+        };
+        let Some(ident) = path.path.get_ident() else {
+            return Binding::Plain;
+        };
+        self.bindings
+            .iter()
+            .rev()
+            .find(|(name, _)| name == ident)
+            .map_or(Binding::Plain, |(_, binding)| *binding)
+    }
+
+    /// With the `spawn_thread` option, run tasks spawned on an async runtime
+    /// in a thread instead:
+    /// - `spawn(fut)` becomes `std::thread::spawn(move || fut)`
+    /// - `spawn_blocking(f)` becomes `std::thread::spawn(f)`, and
+    ///   `spawn_blocking(f).await` a plain call `f()`, in an `Ok` for `tokio`
+    /// - awaiting a `JoinHandle`, directly or through a variable, becomes
+    ///   `join()`
+    fn spawn_thread(&self, node: &Expr) -> Option<Expr> {
+        match node {
+            Expr::Await(expr) => match spawn_call(&expr.base, &self.imports) {
+                Some(spawn) if spawn.blocking => Some(call_blocking(&spawn)),
+                Some(spawn) => Some(join_thread(&thread_spawn(&spawn), spawn.result)),
+                None => match self.binding(&expr.base) {
+                    Binding::JoinHandle { result } => Some(join_thread(&expr.base, result)),
+                    _ => None,
+                },
+            },
+            Expr::Call(_) => spawn_call(node, &self.imports).map(|spawn| thread_spawn(&spawn)),
+            _ => None,
+        }
     }
 }
 
 impl VisitMut for AsyncAwaitRemoval {
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        let scope = self.bindings.len();
+        visit_mut::visit_block_mut(self, block);
+        self.bindings.truncate(scope);
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        // `join!(a, b);` or `select! {..}` in statement position is not an
+        // expression yet, make it one so that it is converted below like any
+        // other `join!` or `select!`
+        if let Stmt::Macro(stmt_macro) = stmt {
+            if join_macro(&stmt_macro.mac, &self.imports).is_some()
+                || is_select_macro(&stmt_macro.mac, &self.imports)
+            {
+                let expr = Expr::Macro(ExprMacro {
+                    attrs: mem::take(&mut stmt_macro.attrs),
+                    mac: stmt_macro.mac.clone(),
+                });
+                *stmt = Stmt::Expr(expr, stmt_macro.semi_token);
+            }
+        }
+        visit_mut::visit_stmt_mut(self, stmt);
+    }
+
+    fn visit_local_mut(&mut self, local: &mut Local) {
+        let spawn = match &local.init {
+            Some(init) if self.options.spawn_thread => spawn_call(&init.expr, &self.imports),
+            _ => None,
+        };
+        let binding = if let Some(spawn) = spawn {
+            Binding::JoinHandle {
+                result: spawn.result,
+            }
+        } else if self.options.lazy && self.make_lazy(local) {
+            Binding::Lazy
+        } else if let (Some(init), Pat::Ident(pat)) = (&local.init, &local.pat) {
+            // a deferred async block moved into another variable stays deferred
+            if self.binding(&init.expr) == Binding::Lazy && init.diverge.is_none() {
+                self.bindings.push((pat.ident.clone(), Binding::Lazy));
+                return;
+            }
+            Binding::Plain
+        } else {
+            Binding::Plain
+        };
+        visit_mut::visit_local_mut(self, local);
+        if let Pat::Ident(pat) = &local.pat {
+            self.bindings.push((pat.ident.clone(), binding));
+        }
+    }
+
     fn visit_expr_mut(&mut self, node: &mut Expr) {
+        if let Some(error) = async_only_expr(node, &self.imports) {
+            *node = Expr::Verbatim(error.to_compile_error());
+            return;
+        }
+
+        // a deferred async block runs where it is awaited, and nowhere else
+        if let Expr::Await(expr) = node {
+            let base = self.strip_future_adapters((*expr.base).clone());
+            if self.binding(&base) == Binding::Lazy {
+                *node = parse_quote!(#base());
+                return;
+            }
+        }
+        if self.binding(node) == Binding::Lazy {
+            let error = Error::new_spanned(
+                &*node,
+                "an async block deferred by `lazy` can only be awaited or moved into another \
+                 variable with `let`, use `sync_impl` and `async_impl` instead",
+            );
+            *node = Expr::Verbatim(error.to_compile_error());
+            return;
+        }
+
         // Unwrap `Box::pin(async {..})` / `Box::new(async {..})` BEFORE recursing,
         // so the inner async block remains visible to the Async match-arm below.
         if let Some(unwrapped) = unwrap_box_call_with_async(node) {
             *node = unwrapped;
         }
+        // spawned async blocks likewise, to turn them into the closure run by
+        // the thread
+        if self.options.spawn_thread {
+            if let Some(thread) = self.spawn_thread(node) {
+                *node = thread;
+            }
+        }
 
         // Delegate to the default impl to visit nested expressions.
         visit_mut::visit_expr_mut(self, node);
 
         match node {
-            Expr::Await(expr) => *node = (*expr.base).clone(),
+            Expr::Await(expr) => {
+                *node = self.strip_future_adapters((*expr.base).clone());
+            }
 
             Expr::Async(expr) => {
                 let inner = &expr.block;
-                let sync_expr = if let [Stmt::Expr(expr, None)] = inner.stmts.as_slice() {
+                let sync_expr = if has_early_exit(inner) {
+                    // `?` and `return` only leave the async block, keep that by
+                    // turning it into an immediately invoked closure
+                    let capture = &expr.capture;
+                    let mut closure: ExprClosure = parse_quote!(#capture || #inner);
+                    closure.attrs = expr.attrs.clone();
+                    parse_quote!((#closure)())
+                } else if let [Stmt::Expr(expr, None)] = inner.stmts.as_slice() {
                     // remove useless braces when there is only one statement
                     expr.clone()
                 } else {
@@ -122,71 +495,736 @@ impl VisitMut for AsyncAwaitRemoval {
                 };
                 *node = sync_expr;
             }
+
+            // `async |x| ..` / `async move |x| ..` to a plain closure, its body
+            // has already been converted above
+            Expr::Closure(expr) => expr.asyncness = None,
+
+            // `ready(v)`, `ok(v)`, `block_on(fut)`, `pin!(fut)`, `join!(a, b)`,
+            // `join_all(iter)`, ..
+            Expr::Call(_) | Expr::Macro(_) => {
+                if let Some(unwrapped) = unwrap_future_constructor(node, &self.imports)
+                    .or_else(|| sequence_join(node, &self.imports))
+                {
+                    *node = unwrapped;
+                }
+            }
             _ => {}
         }
     }
 
-    fn visit_signature_mut(&mut self, sig: &mut Signature) {
-        // rewrite `-> impl Future<Output = T> + ...`,
-        // `-> Box<dyn Future<Output = T> + ...>`,
-        // `-> Pin<Box<dyn Future<Output = T> + ...>>` to `-> T`
-        if let ReturnType::Type(arrow, ty) = &sig.output {
-            if let Some(inner) = extract_future_output(ty) {
-                sig.output = ReturnType::Type(*arrow, Box::new(inner));
+    fn visit_macro_mut(&mut self, mac: &mut Macro) {
+        // arguments of most macros, like `assert_eq!` or `format!`, are comma
+        // separated expressions and are converted as such. For any other macro
+        // only the `.await` tokens are removed.
+        let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+        if let Ok(mut args) = parser.parse2(mac.tokens.clone()) {
+            for arg in &mut args {
+                self.visit_expr_mut(arg);
             }
+            mac.tokens = quote!(#args);
+        } else {
+            mac.tokens = remove_await_tokens(mac.tokens.clone());
+        }
+    }
+
+    // find generic parameters of Future and replace them with their Output
+    // type, on fns, methods, impls, traits, structs and enums
+
+    fn visit_item_fn_mut(&mut self, i: &mut ItemFn) {
+        for (generic_type_name, output) in self.search_future_generics(&i.sig.generics) {
+            let mut replace = ReplaceGenericType::new(&generic_type_name, &output);
+            replace.remove_generic_type(&mut i.sig.generics);
+            replace.visit_item_fn_mut(i);
+        }
+        visit_mut::visit_item_fn_mut(self, i);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, i: &mut ImplItemFn) {
+        if has_maybe_async_attr(&i.attrs) {
+            return;
+        }
+        for (generic_type_name, output) in self.search_future_generics(&i.sig.generics) {
+            let mut replace = ReplaceGenericType::new(&generic_type_name, &output);
+            replace.remove_generic_type(&mut i.sig.generics);
+            replace.visit_impl_item_fn_mut(i);
+        }
+        visit_mut::visit_impl_item_fn_mut(self, i);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, i: &mut TraitItemFn) {
+        if has_maybe_async_attr(&i.attrs) {
+            return;
+        }
+        for (generic_type_name, output) in self.search_future_generics(&i.sig.generics) {
+            let mut replace = ReplaceGenericType::new(&generic_type_name, &output);
+            replace.remove_generic_type(&mut i.sig.generics);
+            replace.visit_trait_item_fn_mut(i);
+        }
+        visit_mut::visit_trait_item_fn_mut(self, i);
+    }
+
+    fn visit_item_impl_mut(&mut self, i: &mut ItemImpl) {
+        for (generic_type_name, output) in self.search_future_generics(&i.generics) {
+            let mut replace = ReplaceGenericType::new(&generic_type_name, &output);
+            replace.remove_generic_type(&mut i.generics);
+            replace.visit_item_impl_mut(i);
+        }
+        visit_mut::visit_item_impl_mut(self, i);
+    }
+
+    fn visit_item_trait_mut(&mut self, i: &mut ItemTrait) {
+        for (generic_type_name, output) in self.search_future_generics(&i.generics) {
+            let mut replace = ReplaceGenericType::new(&generic_type_name, &output);
+            replace.remove_generic_bounds(&mut i.generics);
+            replace.visit_item_trait_mut(i);
         }
+        visit_mut::visit_item_trait_mut(self, i);
+    }
+
+    fn visit_item_struct_mut(&mut self, i: &mut ItemStruct) {
+        // the impls give the output type to the generic type, so a field of
+        // the generic type holds the output
+        for (generic_type_name, output) in self.search_future_generics(&i.generics) {
+            ReplaceGenericType::new(&generic_type_name, &output)
+                .remove_generic_bounds(&mut i.generics);
+        }
+        visit_mut::visit_item_struct_mut(self, i);
+    }
+
+    fn visit_item_enum_mut(&mut self, i: &mut ItemEnum) {
+        for (generic_type_name, output) in self.search_future_generics(&i.generics) {
+            ReplaceGenericType::new(&generic_type_name, &output)
+                .remove_generic_bounds(&mut i.generics);
+        }
+        visit_mut::visit_item_enum_mut(self, i);
+    }
+
+    fn visit_signature_mut(&mut self, sig: &mut Signature) {
+        // every fn, method and nested fn that is converted loses its `async`
+        sig.asyncness = None;
         visit_mut::visit_signature_mut(self, sig);
     }
 
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        // rewrite future types to their output wherever a type appears: return
+        // types and arguments, closure bounds `F: Fn(A) -> BoxFuture<'_, T>`,
+        // fn pointers, fields, let bindings, casts and generic arguments
+        if let Some(error) = async_only_type(ty, &self.imports) {
+            *ty = Type::Verbatim(error.to_compile_error());
+            return;
+        }
+        if let Some(inner) = extract_future_output(ty, &self.options.future_aliases) {
+            *ty = inner;
+        }
+        visit_mut::visit_type_mut(self, ty);
+    }
+
+    fn visit_trait_bound_mut(&mut self, i: &mut TraitBound) {
+        // `AsyncFn(A) -> T` to `Fn(A) -> T`, likewise for `AsyncFnMut` and
+        // `AsyncFnOnce`
+        if let Some(seg) = i.path.segments.last_mut() {
+            let sync_name = match seg.ident.to_string().as_str() {
+                "AsyncFn" => Some("Fn"),
+                "AsyncFnMut" => Some("FnMut"),
+                "AsyncFnOnce" => Some("FnOnce"),
+                _ => None,
+            };
+            if let Some(sync_name) = sync_name {
+                seg.ident = Ident::new(sync_name, seg.ident.span());
+            }
+        }
+        visit_mut::visit_trait_bound_mut(self, i);
+    }
+
     fn visit_item_mut(&mut self, i: &mut Item) {
-        // find generic parameter of Future and replace it with its Output type
-        if let Item::Fn(item_fn) = i {
-            let mut inputs: Vec<(String, PathSegment)> = vec![];
+        // nested items marked by another `maybe_async` attribute are converted
+        // by that attribute
+        if has_maybe_async_attr(item_attrs(i)) {
+            return;
+        }
+        // a hand written future has no sync equivalent, drop it, or keep it
+        // as is along with a blocking `wait` method with the `wait` option
+        if let Item::Impl(item) = i {
+            if is_future_impl(item) {
+                let tokens = if self.options.wait {
+                    let wait = wait_impl(item);
+                    quote!(#item #wait)
+                } else {
+                    TokenStream::new()
+                };
+                *i = Item::Verbatim(tokens);
+                return;
+            }
+        }
+        // local variables are not visible inside nested items
+        let bindings = mem::take(&mut self.bindings);
+        visit_item_mut(self, i);
+        self.bindings = bindings;
+    }
+}
 
-            // generic params: <T:Future<Output=()>, F>
-            for param in &item_fn.sig.generics.params {
-                // generic param: T:Future<Output=()>
-                if let GenericParam::Type(type_param) = param {
-                    let generic_type_name = type_param.ident.to_string();
+/// Crates whose `ASYNC_IMPORTS` and `IO_TRAITS` are handled, the same names
+/// imported from any other crate or module are kept as is.
+const ASYNC_CRATES: &[&str] = &[
+    "std",
+    "core",
+    "futures",
+    "futures_util",
+    "futures_core",
+    "tokio",
+    "async_std",
+    "tracing",
+];
+
+/// Imports only used by async code, dropped in sync mode.
+const ASYNC_IMPORTS: &[&str] = &[
+    "Future",
+    "IntoFuture",
+    "Pin",
+    "FutureExt",
+    "TryFutureExt",
+    "StreamExt",
+    "TryStreamExt",
+    "BoxFuture",
+    "LocalBoxFuture",
+    "Instrument",
+    "WithSubscriber",
+];
+
+/// Extension traits of async IO, of `tokio` or `futures`, imported as their
+/// `std::io` counterpart in sync mode, as `(async trait, sync trait)`.
+const IO_TRAITS: &[(&str, &str)] = &[
+    ("AsyncReadExt", "Read"),
+    ("AsyncWriteExt", "Write"),
+    ("AsyncBufReadExt", "BufRead"),
+    ("AsyncSeekExt", "Seek"),
+];
+
+/// Async modules and items imported from their blocking counterpart in sync
+/// mode, as `(async path, sync path)`.
+const IMPORT_MAP: &[(&str, &str)] = &[
+    ("tokio::fs", "std::fs"),
+    ("tokio::io", "std::io"),
+    ("tokio::net", "std::net"),
+    ("tokio::time::sleep", "std::thread::sleep"),
+    ("tokio::time::Duration", "std::time::Duration"),
+    ("tokio::time::Instant", "std::time::Instant"),
+    ("async_std::fs", "std::fs"),
+    ("async_std::io", "std::io"),
+    ("async_std::net", "std::net"),
+    ("async_std::task::sleep", "std::thread::sleep"),
+];
+
+/// The names the converted item refers to, outside of its imports, so that
+/// an async import that is still needed is kept.
+#[derive(Default)]
+struct References(HashSet<String>);
+
+impl References {
+    fn collect(file: &File) -> HashSet<String> {
+        let mut references = Self::default();
+        references.visit_file(file);
+        references.0
+    }
+
+    fn visit_tokens(&mut self, tokens: TokenStream) {
+        for token in tokens {
+            match token {
+                TokenTree::Ident(ident) => {
+                    self.0.insert(ident.to_string());
+                }
+                TokenTree::Group(group) => self.visit_tokens(group.stream()),
+                TokenTree::Punct(_) | TokenTree::Literal(_) => {}
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for References {
+    fn visit_ident(&mut self, node: &'ast Ident) {
+        self.0.insert(node.to_string());
+    }
+
+    fn visit_item(&mut self, node: &'ast Item) {
+        // a hand written future kept by the `wait` option
+        match node {
+            Item::Verbatim(tokens) => self.visit_tokens(tokens.clone()),
+            _ => visit::visit_item(self, node),
+        }
+    }
+
+    fn visit_item_use(&mut self, _: &'ast ItemUse) {}
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        visit::visit_macro(self, node);
+        self.visit_tokens(node.tokens.clone());
+    }
+}
+
+/// Convert the imports of an item: those only used by async code are dropped,
+/// unless the item still refers to them, and async modules of runtimes are
+/// imported from `std` instead.
+struct SyncUses<'a>(&'a HashSet<String>);
+
+impl VisitMut for SyncUses<'_> {
+    fn visit_item_mut(&mut self, i: &mut Item) {
+        if has_maybe_async_attr(item_attrs(i)) {
+            return;
+        }
+        if let Item::Use(item) = i {
+            let attrs = &item.attrs;
+            let vis = &item.vis;
+            let leading_colon = &item.leading_colon;
+            let uses =
+                sync_use_paths(&item.tree, self.0)
+                    .into_iter()
+                    .map(|(path, async_import)| {
+                        // the name may as well refer to something else, like a field
+                        let allow = async_import.then(|| quote!(#[allow(unused_imports)]));
+                        quote!(#(#attrs)* #allow #vis use #leading_colon #path;)
+                    });
+            *i = Item::Verbatim(quote!(#(#uses)*));
+            return;
+        }
+        visit_item_mut(self, i);
+    }
+}
 
-                    // bound: Future<Output=()>
-                    for bound in &type_param.bounds {
-                        inputs.extend(search_trait_bound(&generic_type_name, bound));
+/// Flatten the imports of `tree` into one path each, dropping the
+/// `ASYNC_IMPORTS` the converted item no longer refers to, per `references`,
+/// and remapping those in `IO_TRAITS` and `IMPORT_MAP`. Each path comes with
+/// whether it is a kept async import.
+fn sync_use_paths(tree: &UseTree, references: &HashSet<String>) -> Vec<(TokenStream, bool)> {
+    let mut flat = vec![];
+    flatten_use_tree(tree, &mut vec![], &mut flat);
+    flat.into_iter()
+        .filter_map(|(mut prefix, mut leaf)| {
+            let is_self = match &leaf {
+                UseTree::Name(name) => name.ident == "self",
+                UseTree::Rename(rename) => rename.ident == "self",
+                _ => false,
+            };
+            // the name the import binds, `_` for a trait imported anonymously
+            let bound = match &leaf {
+                UseTree::Name(name) => Some(name.ident.to_string()),
+                UseTree::Rename(rename) => Some(rename.rename.to_string()),
+                _ => None,
+            };
+            let mut async_import = false;
+            let mut name = match &mut leaf {
+                UseTree::Name(name) if !is_self => Some(&mut name.ident),
+                UseTree::Rename(rename) if !is_self => Some(&mut rename.ident),
+                _ => None,
+            };
+            let from_async_crate = prefix
+                .first()
+                .is_some_and(|first| ASYNC_CRATES.iter().any(|krate| first == krate));
+            if let Some(name) = name.as_ref().filter(|_| from_async_crate) {
+                if ASYNC_IMPORTS.iter().any(|import| *name == import) {
+                    let referenced = bound
+                        .filter(|bound| bound != "_")
+                        .is_some_and(|bound| references.contains(&bound));
+                    if !referenced {
+                        return None;
                     }
+                    async_import = true;
+                }
+                // imported anonymously, not to conflict with another import
+                // of the `std` trait
+                if let Some((_, io_trait)) = IO_TRAITS.iter().find(|(from, _)| *name == from) {
+                    let io_trait = Ident::new(io_trait, name.span());
+                    return Some((quote!(std::io::#io_trait as _), false));
                 }
             }
 
-            if let Some(where_clause) = &item_fn.sig.generics.where_clause {
-                for predicate in &where_clause.predicates {
-                    if let WherePredicate::Type(predicate_type) = predicate {
-                        let generic_type_name = if let Type::Path(p) = &predicate_type.bounded_ty {
-                            p.path.segments[0].ident.to_string()
-                        } else {
-                            panic!("Please submit an issue");
-                        };
-
-                        for bound in &predicate_type.bounds {
-                            inputs.extend(search_trait_bound(&generic_type_name, bound));
-                        }
+            let path: Vec<_> = prefix.iter().chain(name.as_deref()).collect();
+            if let Some(mut path) = remap_import(&path) {
+                // the last segment keeps its rename if any
+                if let Some(name) = &mut name {
+                    **name = path.pop()?;
+                }
+                prefix = path;
+            }
+
+            // `self` is only allowed in braces: `use std::io::{self};`
+            let path = if is_self {
+                quote!(#(#prefix::)* {#leaf})
+            } else {
+                quote!(#(#prefix::)* #leaf)
+            };
+            Some((path, async_import))
+        })
+        .collect()
+}
+
+/// Remap the import of `path` with `IMPORT_MAP`, matching whole segments, so
+/// `tokio::fs::File` becomes `std::fs::File`.
+fn remap_import(path: &[&Ident]) -> Option<Vec<Ident>> {
+    IMPORT_MAP.iter().find_map(|(from, to)| {
+        let from: Vec<_> = from.split("::").collect();
+        let matches =
+            path.len() >= from.len() && path.iter().zip(&from).all(|(seg, from)| *seg == from);
+        matches.then(|| {
+            let span = path[0].span();
+            to.split("::")
+                .map(|seg| Ident::new(seg, span))
+                .chain(path[from.len()..].iter().map(|seg| (*seg).clone()))
+                .collect()
+        })
+    })
+}
+
+/// Collect the paths of `tree` as their prefix and last segment, a name, a
+/// rename or a glob.
+fn flatten_use_tree(
+    tree: &UseTree,
+    prefix: &mut Vec<Ident>,
+    flat: &mut Vec<(Vec<Ident>, UseTree)>,
+) {
+    match tree {
+        UseTree::Path(path) => {
+            prefix.push(path.ident.clone());
+            flatten_use_tree(&path.tree, prefix, flat);
+            prefix.pop();
+        }
+        UseTree::Group(group) => {
+            for tree in &group.items {
+                flatten_use_tree(tree, prefix, flat);
+            }
+        }
+        leaf => flat.push((prefix.clone(), leaf.clone())),
+    }
+}
+
+/// The imports of a converted item, as the name they bind and the path it
+/// stands for.
+#[derive(Default)]
+struct Imports(Vec<(Ident, Vec<Ident>)>);
+
+impl Imports {
+    fn collect(file: &File) -> Self {
+        let mut imports = Self::default();
+        imports.visit_file(file);
+        imports
+    }
+
+    /// The full path of `path`, whose first segment is replaced by the path it
+    /// is imported from, if any, so `ready` is `futures::future::ready` after
+    /// `use futures::future::ready;`. Generic arguments are left out.
+    fn resolve(&self, path: &Path) -> String {
+        let mut segs: Vec<_> = path.segments.iter().map(|seg| seg.ident.clone()).collect();
+        let imported = match segs.first() {
+            Some(first) if path.leading_colon.is_none() => {
+                self.0.iter().rev().find(|(name, _)| name == first)
+            }
+            _ => None,
+        };
+        if let Some((_, full)) = imported {
+            segs.splice(..1, full.iter().cloned());
+        }
+        let segs: Vec<_> = segs.iter().map(Ident::to_string).collect();
+        segs.join("::")
+    }
+}
+
+impl<'ast> Visit<'ast> for Imports {
+    fn visit_item_use(&mut self, node: &'ast ItemUse) {
+        let mut flat = vec![];
+        flatten_use_tree(&node.tree, &mut vec![], &mut flat);
+        for (mut path, leaf) in flat {
+            let name = match leaf {
+                UseTree::Name(name) if name.ident == "self" => match path.last() {
+                    Some(last) => last.clone(),
+                    None => continue,
+                },
+                UseTree::Name(name) => {
+                    path.push(name.ident.clone());
+                    name.ident
+                }
+                UseTree::Rename(rename) => {
+                    if rename.ident != "self" {
+                        path.push(rename.ident);
+                    }
+                    rename.rename
+                }
+                UseTree::Path(_) | UseTree::Glob(_) | UseTree::Group(_) => continue,
+            };
+            self.0.push((name, path));
+        }
+    }
+}
+
+/// If `item` implements `Future` or `IntoFuture` by hand.
+pub fn is_future_impl(item: &ItemImpl) -> bool {
+    item.trait_
+        .as_ref()
+        .and_then(|(_, path, _)| path.segments.last())
+        .is_some_and(|seg| seg.ident == "Future" || seg.ident == "IntoFuture")
+}
+
+/// The `wait` method of the `wait` option, blocking on the future of `item`
+/// with a minimal executor: poll it, and park the thread until it is woken up.
+fn wait_impl(item: &ItemImpl) -> ItemImpl {
+    let attrs = &item.attrs;
+    let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+    let self_ty = &item.self_ty;
+    parse_quote! {
+        #(#attrs)*
+        impl #impl_generics #self_ty #where_clause {
+            pub fn wait(self) -> <Self as ::core::future::IntoFuture>::Output {
+                struct ThreadWaker(::std::thread::Thread);
+
+                impl ::std::task::Wake for ThreadWaker {
+                    fn wake(self: ::std::sync::Arc<Self>) {
+                        self.0.unpark();
+                    }
+                }
+
+                let waker = ::std::task::Waker::from(::std::sync::Arc::new(ThreadWaker(
+                    ::std::thread::current(),
+                )));
+                let mut cx = ::std::task::Context::from_waker(&waker);
+                let mut future = ::core::pin::pin!(::core::future::IntoFuture::into_future(self));
+                loop {
+                    let poll = ::core::future::Future::poll(future.as_mut(), &mut cx);
+                    if let ::core::task::Poll::Ready(output) = poll {
+                        return output;
+                    }
+                    ::std::thread::park();
+                }
+            }
+        }
+    }
+}
+
+/// With the `lazy` option, finds the async blocks that cannot be deferred, as
+/// they are neither bound with `let`, awaited in place, returned, spawned nor
+/// joined, and would silently run where they are defined in sync code.
+struct LazyCheck<'a> {
+    removal: &'a AsyncAwaitRemoval,
+    errors: Vec<Error>,
+}
+
+impl LazyCheck<'_> {
+    /// Visit `expr` in a position where an async block runs where it is
+    /// defined in async code as well, like the branches of an awaited `if`.
+    fn visit_in_place(&mut self, expr: &Expr) {
+        let expr = unwrap_box_call_with_async(expr).unwrap_or_else(|| expr.clone());
+        match &expr {
+            Expr::Async(expr) => self.visit_block(&expr.block),
+            Expr::Block(expr) => self.visit_body(&expr.block),
+            Expr::If(expr) => {
+                self.visit_expr(&expr.cond);
+                self.visit_body(&expr.then_branch);
+                if let Some((_, else_branch)) = &expr.else_branch {
+                    self.visit_in_place(else_branch);
+                }
+            }
+            Expr::Match(expr) => {
+                self.visit_expr(&expr.expr);
+                for arm in &expr.arms {
+                    if let Some((_, guard)) = &arm.guard {
+                        self.visit_expr(guard);
                     }
+                    self.visit_in_place(&arm.body);
                 }
             }
+            expr => self.visit_expr(expr),
+        }
+    }
 
-            for (generic_type_name, path_seg) in &inputs {
-                ReplaceGenericType::replace_generic_type(i, generic_type_name, path_seg);
+    /// Visit a block whose value is used in place, like the block of a fn,
+    /// whose value is returned.
+    fn visit_body(&mut self, block: &Block) {
+        for stmt in &block.stmts {
+            match stmt {
+                Stmt::Expr(expr, None) => self.visit_in_place(expr),
+                stmt => self.visit_stmt(stmt),
             }
         }
-        visit_item_mut(self, i);
     }
+
+    /// Visit the comma separated arguments of a macro, if they are.
+    fn visit_macro_args(&mut self, mac: &Macro, in_place: bool) {
+        let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+        let Ok(args) = parser.parse2(mac.tokens.clone()) else {
+            return;
+        };
+        for arg in &args {
+            if in_place {
+                self.visit_in_place(arg);
+            } else {
+                self.visit_expr(arg);
+            }
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for LazyCheck<'_> {
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        let imports = &self.removal.imports;
+        match node {
+            Expr::Async(expr) => {
+                self.errors.push(Error::new_spanned(
+                    expr.async_token,
+                    "`lazy` can only defer an async block bound to a variable with `let`, \
+                     this one would run where it is defined in sync code, bind it with `let` \
+                     or use `sync_impl` and `async_impl` instead",
+                ));
+                self.visit_block(&expr.block);
+            }
+            Expr::Await(expr) => {
+                let base = self.removal.strip_future_adapters((*expr.base).clone());
+                self.visit_in_place(&base);
+            }
+            Expr::Closure(expr) => self.visit_in_place(&expr.body),
+            Expr::Return(ExprReturn {
+                expr: Some(expr), ..
+            }) => self.visit_in_place(expr),
+            Expr::Call(call) if spawn_call(node, imports).is_some() => {
+                self.visit_in_place(&call.args[0]);
+            }
+            Expr::Call(call) if join_function(call, imports).is_some() => {
+                for arg in &call.args {
+                    match arg {
+                        Expr::Macro(arg) => self.visit_macro_args(&arg.mac, true),
+                        arg => self.visit_in_place(arg),
+                    }
+                }
+            }
+            _ => visit::visit_expr(self, node),
+        }
+    }
+
+    fn visit_item(&mut self, node: &'ast Item) {
+        // converted by their own attribute
+        if !has_maybe_async_attr(item_attrs(node)) {
+            visit::visit_item(self, node);
+        }
+    }
+
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.visit_signature(&node.sig);
+        self.visit_body(&node.block);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        if !has_maybe_async_attr(&node.attrs) {
+            self.visit_signature(&node.sig);
+            self.visit_body(&node.block);
+        }
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        if has_maybe_async_attr(&node.attrs) {
+            return;
+        }
+        if let Some(block) = &node.default {
+            self.visit_body(block);
+        }
+    }
+
+    fn visit_local(&mut self, node: &'ast Local) {
+        let deferred = match (&node.pat, &node.init) {
+            (Pat::Ident(_) | Pat::Type(_), Some(init)) if init.diverge.is_none() => Some(init),
+            _ => None,
+        };
+        // only an async block bound as is is deferred, not the branches of an
+        // `if` for instance
+        let init = deferred.map(|init| {
+            unwrap_box_call_with_async(&init.expr).unwrap_or_else(|| (*init.expr).clone())
+        });
+        match init {
+            Some(Expr::Async(expr)) => self.visit_block(&expr.block),
+            Some(init) => {
+                self.visit_pat(&node.pat);
+                self.visit_expr(&init);
+            }
+            None => visit::visit_local(self, node),
+        }
+    }
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        // the arguments of `join!` are polled right away in async code too
+        let in_place = join_macro(node, &self.removal.imports).is_some();
+        self.visit_macro_args(node, in_place);
+    }
+}
+
+/// Finds `?` and `return` that would leave the visited block, skipping
+/// closures and nested items which have their own scope.
+#[derive(Default)]
+struct EarlyExit(bool);
+
+impl<'ast> Visit<'ast> for EarlyExit {
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        match node {
+            Expr::Try(_) | Expr::Return(_) => self.0 = true,
+            Expr::Closure(_) | Expr::Async(_) => {}
+            _ => visit::visit_expr(self, node),
+        }
+    }
+
+    fn visit_item(&mut self, _: &'ast Item) {}
+
+    fn visit_macro(&mut self, node: &'ast Macro) {
+        // macro arguments are not parsed, look for the tokens instead
+        self.0 |= tokens_have_early_exit(node.tokens.clone());
+    }
+}
+
+fn tokens_have_early_exit(tokens: TokenStream) -> bool {
+    tokens.into_iter().any(|tt| match tt {
+        TokenTree::Punct(p) => p.as_char() == '?',
+        TokenTree::Ident(i) => i == "return",
+        TokenTree::Group(g) => tokens_have_early_exit(g.stream()),
+        TokenTree::Literal(_) => false,
+    })
+}
+
+fn has_early_exit(block: &Block) -> bool {
+    let mut visitor = EarlyExit::default();
+    visitor.visit_block(block);
+    visitor.0
+}
+
+/// Remove every `. await` token sequence, including the ones nested in groups.
+fn remove_await_tokens(tokens: TokenStream) -> TokenStream {
+    let mut output = vec![];
+    let mut iter = tokens.into_iter().peekable();
+    while let Some(tt) = iter.next() {
+        match tt {
+            TokenTree::Punct(p)
+                if p.as_char() == '.'
+                    && matches!(iter.peek(), Some(TokenTree::Ident(i)) if i == "await") =>
+            {
+                iter.next();
+            }
+            TokenTree::Group(g) => {
+                let mut group = Group::new(g.delimiter(), remove_await_tokens(g.stream()));
+                group.set_span(g.span());
+                output.push(TokenTree::Group(group));
+            }
+            tt => output.push(tt),
+        }
+    }
+    output.into_iter().collect()
 }
 
+/// Future aliases recognised without being declared with `future_alias`.
+const FUTURE_ALIASES: &[&str] = &["BoxFuture", "LocalBoxFuture"];
+
 /// Extract `T` from any of:
 /// - `impl Future<Output = T> + ...`
 /// - `Box<dyn Future<Output = T> + ...>`
 /// - `Pin<Box<dyn Future<Output = T> + ...>>`
+/// - `BoxFuture<'a, T>`, `LocalBoxFuture<'a, T>` and the `future_aliases`,
+///   whose first type argument is `T`
+///
 /// Paths are matched by last segment name only, so `std::pin::Pin`,
 /// `core::pin::Pin`, etc. all match.
-fn extract_future_output(ty: &Type) -> Option<Type> {
+fn extract_future_output(ty: &Type, future_aliases: &[String]) -> Option<Type> {
     match ty {
         Type::ImplTrait(impl_trait) => extract_future_output_from_bounds(impl_trait.bounds.iter()),
         Type::TraitObject(trait_obj) => extract_future_output_from_bounds(trait_obj.bounds.iter()),
@@ -196,14 +1234,20 @@ fn extract_future_output(ty: &Type) -> Option<Type> {
             let PathArguments::AngleBracketed(args) = &seg.arguments else {
                 return None;
             };
+            let mut type_args = args.args.iter().filter_map(|arg| {
+                if let GenericArgument::Type(inner) = arg {
+                    Some(inner)
+                } else {
+                    None
+                }
+            });
             match name.as_str() {
-                "Pin" | "Box" => args.args.iter().find_map(|arg| {
-                    if let GenericArgument::Type(inner) = arg {
-                        extract_future_output(inner)
-                    } else {
-                        None
-                    }
-                }),
+                "Pin" | "Box" => {
+                    type_args.find_map(|inner| extract_future_output(inner, future_aliases))
+                }
+                _ if FUTURE_ALIASES.contains(&name.as_str()) || future_aliases.contains(&name) => {
+                    type_args.next().cloned()
+                }
                 _ => None,
             }
         }
@@ -221,7 +1265,7 @@ fn extract_future_output_from_bounds<'a>(
         let Some(seg) = trait_bound.path.segments.last() else {
             continue;
         };
-        if seg.ident.to_string() != "Future" {
+        if seg.ident != "Future" {
             continue;
         }
         let PathArguments::AngleBracketed(args) = &seg.arguments else {
@@ -229,7 +1273,7 @@ fn extract_future_output_from_bounds<'a>(
         };
         for arg in &args.args {
             if let GenericArgument::AssocType(assoc) = arg {
-                if assoc.ident.to_string() == "Output" {
+                if assoc.ident == "Output" {
                     return Some(assoc.ty.clone());
                 }
             }
@@ -238,11 +1282,10 @@ fn extract_future_output_from_bounds<'a>(
     None
 }
 
-/// If `node` is `Box::pin(<async block>)` or `Box::new(<async block>)`,
-/// return the async block. Path is matched on the last two segments being
-/// `Box` then `pin`/`new`, so qualified paths like `::std::boxed::Box::pin`
-/// also match.
-fn unwrap_box_call_with_async(node: &Expr) -> Option<Expr> {
+/// If `node` is `Box::pin(<expr>)` or `Box::new(<expr>)`, return the
+/// expression. Path is matched on the last two segments being `Box` then
+/// `pin`/`new`, so qualified paths like `::std::boxed::Box::pin` also match.
+fn unwrap_box_call(node: &Expr) -> Option<Expr> {
     let Expr::Call(call) = node else { return None };
     let Expr::Path(path_expr) = call.func.as_ref() else {
         return None;
@@ -259,32 +1302,341 @@ fn unwrap_box_call_with_async(node: &Expr) -> Option<Expr> {
     if call.args.len() != 1 {
         return None;
     }
-    if !matches!(&call.args[0], Expr::Async(_)) {
-        return None;
-    }
     Some(call.args[0].clone())
 }
 
-fn search_trait_bound(
-    generic_type_name: &str,
-    bound: &TypeParamBound,
-) -> Vec<(String, PathSegment)> {
-    let mut inputs = vec![];
-
-    if let TypeParamBound::Trait(trait_bound) = bound {
-        let segment = &trait_bound.path.segments[trait_bound.path.segments.len() - 1];
-        let name = segment.ident.to_string();
-        if name.eq("Future") {
-            // match Future<Output=Type>
-            if let PathArguments::AngleBracketed(args) = &segment.arguments {
-                // binding: Output=Type
-                if let GenericArgument::AssocType(binding) = &args.args[0] {
-                    if let Type::Path(p) = &binding.ty {
-                        inputs.push((generic_type_name.to_owned(), p.path.segments[0].clone()));
-                    }
+/// If `node` is `Box::pin(<async block>)` or `Box::new(<async block>)`,
+/// return the async block.
+fn unwrap_box_call_with_async(node: &Expr) -> Option<Expr> {
+    unwrap_box_call(node).filter(|inner| matches!(inner, Expr::Async(_)))
+}
+
+/// Future constructors that are identity in blocking code, as
+/// `(path, wrapper)`: a call of `path(x)`, written in full or imported, becomes
+/// `wrapper(x)`, or `x` when there is no wrapper.
+const FUTURE_CONSTRUCTORS: &[(&str, Option<&str>)] = &[
+    ("std::future::ready", None),
+    ("core::future::ready", None),
+    ("futures::future::ready", None),
+    ("futures_util::future::ready", None),
+    ("futures::future::ok", Some("Ok")),
+    ("futures_util::future::ok", Some("Ok")),
+    ("futures::future::err", Some("Err")),
+    ("futures_util::future::err", Some("Err")),
+    ("futures::executor::block_on", None),
+    ("futures_executor::block_on", None),
+];
+
+/// The `pin!` macros, identity in blocking code.
+const PIN_MACROS: &[&str] = &["std::pin::pin", "core::pin::pin"];
+
+/// If `node` is a call to one of the `FUTURE_CONSTRUCTORS` or `PIN_MACROS`,
+/// return its sync equivalent. `ok::<T, E>(v)` keeps its generic arguments as
+/// `Ok::<T, E>(v)`.
+fn unwrap_future_constructor(node: &Expr, imports: &Imports) -> Option<Expr> {
+    match node {
+        Expr::Call(call) => {
+            let Expr::Path(path_expr) = call.func.as_ref() else {
+                return None;
+            };
+            if path_expr.qself.is_some() || call.args.len() != 1 {
+                return None;
+            }
+            let function = path_expr.path.segments.last()?;
+            let resolved = imports.resolve(&path_expr.path);
+            let (_, wrapper) = FUTURE_CONSTRUCTORS
+                .iter()
+                .find(|(path, _)| resolved == *path)?;
+            let arg = &call.args[0];
+            Some(match wrapper {
+                Some(wrapper) => {
+                    let mut wrapper_seg = function.clone();
+                    wrapper_seg.ident = Ident::new(wrapper, function.ident.span());
+                    parse_quote!(#wrapper_seg(#arg))
                 }
+                None => arg.clone(),
+            })
+        }
+        Expr::Macro(expr) => {
+            let resolved = imports.resolve(&expr.mac.path);
+            if !PIN_MACROS.contains(&resolved.as_str()) {
+                return None;
+            }
+            expr.mac.parse_body::<Expr>().ok()
+        }
+        _ => None,
+    }
+}
+
+/// The `spawn` and `spawn_blocking` functions of async runtimes, run in a
+/// thread by the `spawn_thread` option, as `(path, is spawn_blocking, result)`,
+/// where `result` is whether awaiting their `JoinHandle` gives a `Result`, like
+/// `tokio`, rather than the output of the task.
+const SPAWN_FUNCTIONS: &[(&str, bool, bool)] = &[
+    ("tokio::spawn", false, true),
+    ("tokio::task::spawn", false, true),
+    ("tokio::task::spawn_blocking", true, true),
+    ("async_std::task::spawn", false, false),
+    ("async_std::task::spawn_blocking", true, false),
+    ("smol::spawn", false, false),
+];
+
+/// A call of one of the `SPAWN_FUNCTIONS`, written in full or imported.
+struct SpawnCall<'a> {
+    arg: &'a Expr,
+    blocking: bool,
+    result: bool,
+}
+
+fn spawn_call<'a>(node: &'a Expr, imports: &Imports) -> Option<SpawnCall<'a>> {
+    let Expr::Call(call) = node else {
+        return None;
+    };
+    let Expr::Path(ExprPath {
+        qself: None, path, ..
+    }) = call.func.as_ref()
+    else {
+        return None;
+    };
+    if call.args.len() != 1 {
+        return None;
+    }
+    let resolved = imports.resolve(path);
+    let (_, blocking, result) = SPAWN_FUNCTIONS
+        .iter()
+        .find(|(path, _, _)| resolved == *path)?;
+    Some(SpawnCall {
+        arg: &call.args[0],
+        blocking: *blocking,
+        result: *result,
+    })
+}
+
+/// `std::thread::spawn` running the spawned future, or blocking closure.
+fn thread_spawn(spawn: &SpawnCall) -> Expr {
+    let closure: Expr = match spawn.arg {
+        arg if spawn.blocking => arg.clone(),
+        Expr::Async(expr) => {
+            let capture = &expr.capture;
+            let block = &expr.block;
+            parse_quote!(#capture || #block)
+        }
+        arg => parse_quote!(move || #arg),
+    };
+    parse_quote!(::std::thread::spawn(#closure))
+}
+
+/// Call the closure of `spawn_blocking(f)` in place, with the same `Result` as
+/// `join()` of a thread for `tokio`.
+fn call_blocking(spawn: &SpawnCall) -> Expr {
+    let f = spawn.arg;
+    let call: Expr = match f {
+        Expr::Path(_) => parse_quote!(#f()),
+        _ => parse_quote!((#f)()),
+    };
+    if spawn.result {
+        parse_quote!(::core::result::Result::<
+            _,
+            ::std::boxed::Box<dyn ::std::any::Any + ::core::marker::Send>,
+        >::Ok(#call))
+    } else {
+        call
+    }
+}
+
+/// Wait for the thread of `handle`. Like a `tokio` `JoinHandle`, it gives a
+/// `Result` whose error is the panic of the thread, otherwise, like `async_std`,
+/// the panic is resumed.
+fn join_thread(handle: &Expr, result: bool) -> Expr {
+    if result {
+        parse_quote!(#handle.join())
+    } else {
+        parse_quote!(#handle.join().unwrap_or_else(|panic| ::std::panic::resume_unwind(panic)))
+    }
+}
+
+/// The `join!` and `try_join!` macros, as `(path, is try_join)`.
+const JOIN_MACROS: &[(&str, bool)] = &[
+    ("futures::join", false),
+    ("futures::try_join", true),
+    ("futures_util::join", false),
+    ("futures_util::try_join", true),
+    ("tokio::join", false),
+    ("tokio::try_join", true),
+];
+
+/// The `join_all` and `try_join_all` functions, as `(path, is try_join_all)`.
+const JOIN_FUNCTIONS: &[(&str, bool)] = &[
+    ("futures::future::join_all", false),
+    ("futures::future::try_join_all", true),
+    ("futures_util::future::join_all", false),
+    ("futures_util::future::try_join_all", true),
+];
+
+/// If `mac` is one of the `JOIN_MACROS`, written in full or imported, return
+/// whether it is a `try_join!`.
+fn join_macro(mac: &Macro, imports: &Imports) -> Option<bool> {
+    let resolved = imports.resolve(&mac.path);
+    JOIN_MACROS
+        .iter()
+        .find(|(path, _)| resolved == *path)
+        .map(|(_, is_try)| *is_try)
+}
+
+/// If `call` is a call of one of the `JOIN_FUNCTIONS`, written in full or
+/// imported, return whether it is a `try_join_all`.
+fn join_function(call: &ExprCall, imports: &Imports) -> Option<bool> {
+    let Expr::Path(ExprPath {
+        qself: None, path, ..
+    }) = call.func.as_ref()
+    else {
+        return None;
+    };
+    let resolved = imports.resolve(path);
+    JOIN_FUNCTIONS
+        .iter()
+        .find(|(path, _)| resolved == *path)
+        .map(|(_, is_try)| *is_try)
+}
+
+/// Turn concurrent joins into sequential, left to right, evaluation:
+/// - `join!(a, b)` becomes `(a, b)`
+/// - `try_join!(a, b)` becomes `Ok((a?, b?))`, where `?` only leaves the
+///   macro, so the first error is its value
+/// - `join_all(iter)` becomes `iter.into_iter().collect::<Vec<_>>()`, and
+///   `try_join_all(iter)` collects into a `Result<Vec<_>, _>`
+fn sequence_join(node: &Expr, imports: &Imports) -> Option<Expr> {
+    match node {
+        Expr::Macro(expr) => {
+            let is_try = join_macro(&expr.mac, imports)?;
+            let parser = Punctuated::<Expr, Token![,]>::parse_terminated;
+            let args = parser.parse2(expr.mac.tokens.clone()).ok()?;
+            let args = args.iter();
+            if !is_try {
+                return Some(parse_quote!((#(#args,)*)));
+            }
+
+            // a labeled block rather than a closure, so that the error type is
+            // inferred from the arguments and `return` keeps its meaning
+            let label = Lifetime::new("'__maybe_async_try_join", Span::mixed_site());
+            let values: Vec<_> = (0..args.len())
+                .map(|i| format_ident!("__value{}", i, span = Span::mixed_site()))
+                .collect();
+            Some(parse_quote!(#label: {
+                #(
+                    let #values = match #args {
+                        ::core::result::Result::Ok(value) => value,
+                        ::core::result::Result::Err(error) => {
+                            // syn misreads `break 'label ::path`, bind it first
+                            let error = ::core::result::Result::Err(error);
+                            break #label error;
+                        }
+                    };
+                )*
+                ::core::result::Result::Ok((#(#values,)*))
+            }))
+        }
+        Expr::Call(call) => {
+            if call.args.len() != 1 {
+                return None;
+            }
+            let is_try = join_function(call, imports)?;
+            let iter = &call.args[0];
+            let iter = quote!(::core::iter::IntoIterator::into_iter(#iter));
+            Some(if is_try {
+                parse_quote!(#iter.collect::<::core::result::Result<::std::vec::Vec<_>, _>>())
+            } else {
+                parse_quote!(#iter.collect::<::std::vec::Vec<_>>())
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The `select!` macros, with no sync equivalent.
+const SELECT_MACROS: &[&str] = &[
+    "futures::select",
+    "futures::select_biased",
+    "futures_util::select",
+    "futures_util::select_biased",
+    "tokio::select",
+];
+
+/// The `poll_fn` functions, with no sync equivalent.
+const POLL_FUNCTIONS: &[&str] = &[
+    "std::future::poll_fn",
+    "core::future::poll_fn",
+    "futures::future::poll_fn",
+    "futures_util::future::poll_fn",
+];
+
+/// If `mac` is one of the `SELECT_MACROS`, written in full or imported.
+fn is_select_macro(mac: &Macro, imports: &Imports) -> bool {
+    let resolved = imports.resolve(&mac.path);
+    SELECT_MACROS.contains(&resolved.as_str())
+}
+
+/// Error for a construct that only makes sense in async code, so there is no
+/// sync code to convert it to.
+fn async_only_error(path: &Path, suffix: &str) -> Error {
+    let name: Vec<_> = path
+        .segments
+        .iter()
+        .map(|seg| seg.ident.to_string())
+        .collect();
+    let msg = format!(
+        "`{}{suffix}` has no sync equivalent and cannot be converted to sync code \
+         automatically, use `sync_impl` and `async_impl` instead",
+        name.join("::")
+    );
+    Error::new_spanned(path, msg)
+}
+
+/// Find expressions with no sync equivalent: `select!`, `poll_fn(..)`, and
+/// `Poll::Ready`/`Poll::Pending` of manual polling. Like `async_only_type`,
+/// `Poll` is only matched when qualified with `task::`, or imported so.
+fn async_only_expr(node: &Expr, imports: &Imports) -> Option<Error> {
+    match node {
+        Expr::Macro(expr) if is_select_macro(&expr.mac, imports) => {
+            Some(async_only_error(&expr.mac.path, "!"))
+        }
+        Expr::Call(call) => match call.func.as_ref() {
+            Expr::Path(func) if POLL_FUNCTIONS.contains(&imports.resolve(&func.path).as_str()) => {
+                Some(async_only_error(&func.path, ""))
+            }
+            _ => None,
+        },
+        Expr::Path(expr) => {
+            let resolved = imports.resolve(&expr.path);
+            let segs: Vec<_> = resolved.split("::").collect();
+            match segs.as_slice() {
+                [.., "task", "Poll", "Ready" | "Pending"] => Some(async_only_error(&expr.path, "")),
+                _ => None,
             }
         }
+        _ => None,
+    }
+}
+
+/// Find `Poll` and `Context` of `task`, the types of manual polling, with no
+/// sync equivalent. They are only matched when qualified with `task::`, or
+/// imported so by the converted item, so that other `Context` types are left
+/// alone.
+fn async_only_type(ty: &Type, imports: &Imports) -> Option<Error> {
+    let Type::Path(TypePath { qself: None, path }) = ty else {
+        return None;
+    };
+    let resolved = imports.resolve(path);
+    let mut segs = resolved.rsplit("::");
+    let name = segs.next()?;
+    let in_task = segs.next() == Some("task");
+    (in_task && (name == "Poll" || name == "Context")).then(|| async_only_error(path, ""))
+}
+
+/// If `ty` is a plain identifier like a generic type `F`, return it.
+fn generic_type_ident(ty: &Type) -> Option<&Ident> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => p.path.get_ident(),
+        _ => None,
     }
-    inputs
 }
@@ -0,0 +1,35 @@
+mod r#async {
+    maybe_async::include_file!(async(AFIT), "tests/include/async_client.rs");
+}
+
+mod blocking {
+    maybe_async::include_file!(sync, "tests/include/async_client.rs");
+}
+
+mod maybe {
+    maybe_async::include_file!("tests/include/async_client.rs");
+}
+
+fn values() -> std::collections::HashMap<String, u32> {
+    [("a".to_string(), 1), ("b".to_string(), 2)]
+        .into_iter()
+        .collect()
+}
+
+#[test]
+fn test_blocking_client() {
+    let client = blocking::Client(values());
+    assert_eq!(blocking::sum(&client, &["a", "b", "c"]), 3);
+}
+
+#[tokio::test]
+async fn test_async_client() {
+    let client = r#async::Client(values());
+    assert_eq!(r#async::sum(&client, &["a", "b", "c"]).await, 3);
+}
+
+#[maybe_async::test(feature = "is_sync", async(not(feature = "is_sync"), tokio::test))]
+async fn test_maybe_client() {
+    let client = maybe::Client(values());
+    assert_eq!(maybe::sum(&client, &["a", "b"]).await, 3);
+}
@@ -0,0 +1,27 @@
+// Converted to both an async and a blocking client by `tests/include-file.rs`
+
+use std::collections::HashMap;
+
+pub trait Api {
+    async fn get(&self, key: &str) -> Option<u32>;
+
+    async fn get_or_zero(&self, key: &str) -> u32 {
+        self.get(key).await.unwrap_or(0)
+    }
+}
+
+pub struct Client(pub HashMap<String, u32>);
+
+impl Api for Client {
+    async fn get(&self, key: &str) -> Option<u32> {
+        async { self.0.get(key).copied() }.await
+    }
+}
+
+pub async fn sum(client: &Client, keys: &[&str]) -> u32 {
+    let mut total = 0;
+    for key in keys {
+        total += client.get_or_zero(key).await;
+    }
+    total
+}
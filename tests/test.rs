@@ -8,9 +8,32 @@ fn ui() {
     t.pass("tests/ui/05-replace-future-generic-type-with-output.rs");
     t.pass("tests/ui/06-sync_impl_async_impl.rs");
     t.pass("tests/ui/07-impl-future-rpit.rs");
+    t.pass("tests/ui/08-mod.rs");
+    t.pass("tests/ui/09-async-closure.rs");
+    t.pass("tests/ui/10-async-block-early-exit.rs");
+    t.pass("tests/ui/11-lazy-async-block.rs");
+    t.pass("tests/ui/12-await-in-macro.rs");
+    t.pass("tests/ui/13-nested-async-items.rs");
+    t.pass("tests/ui/14-closure-future-bounds.rs");
+    t.pass("tests/ui/15-async-fn-bounds.rs");
+    t.pass("tests/ui/16-future-alias.rs");
+    t.pass("tests/ui/17-future-types.rs");
+    t.pass("tests/ui/18-future-adapters.rs");
+    t.pass("tests/ui/19-future-constructors.rs");
+    t.pass("tests/ui/20-join.rs");
+    t.pass("tests/ui/21-spawn-thread.rs");
+    t.pass("tests/ui/22-future-impl.rs");
+    t.pass("tests/ui/23-future-fields.rs");
+    t.pass("tests/ui/24-use.rs");
+    t.pass("tests/ui/25-items-macro.rs");
 
     t.compile_fail("tests/ui/test_fail/01-empty-test.rs");
     t.compile_fail("tests/ui/test_fail/02-unknown-path.rs");
     t.compile_fail("tests/ui/test_fail/03-async-gt2.rs");
     t.compile_fail("tests/ui/test_fail/04-bad-sync-cond.rs");
+    t.compile_fail("tests/ui/test_fail/05-bad-args.rs");
+    t.compile_fail("tests/ui/test_fail/06-future-bound-on-non-generic.rs");
+    t.compile_fail("tests/ui/test_fail/07-async-only-constructs.rs");
+    t.compile_fail("tests/ui/test_fail/08-parse-error.rs");
+    t.compile_fail("tests/ui/test_fail/09-lazy-not-deferred.rs");
 }
@@ -1,5 +1,5 @@
 #![allow(unused_imports)]
-use std::future::Future;
+use std::{future::Future, pin::Pin};
 
 #[maybe_async::maybe_async]
 pub async fn with_fn<T, F: Sync + std::future::Future<Output = Result<(), ()>>>(
@@ -20,10 +20,124 @@ pub async fn with_fn_where<T, F>(test: T) -> Result<(), ()>
     test().await
 }
 
+pub struct Runner;
+
+#[maybe_async::maybe_async]
+impl Runner {
+    pub async fn run<F: Future<Output = Result<(), ()>>>(&self, f: F) -> Result<(), ()> {
+        f.await
+    }
+}
+
+#[maybe_async::maybe_async(AFIT)]
+trait RunWhere {
+    async fn run_where<F>(&self, f: F) -> Result<(), ()>
+    where
+        F: Future<Output = Result<(), ()>>;
+}
+
+#[maybe_async::maybe_async(AFIT)]
+impl RunWhere for Runner {
+    async fn run_where<F>(&self, f: F) -> Result<(), ()>
+    where
+        F: Future<Output = Result<(), ()>>,
+    {
+        f.await
+    }
+}
+
+pub struct Wrapper<F>(F);
+
+#[maybe_async::maybe_async]
+impl<F: Future<Output = Result<(), ()>>> Wrapper<F> {
+    pub async fn wait(self) -> Result<(), ()> {
+        self.0.await
+    }
+}
+
+#[maybe_async::maybe_async(AFIT)]
+trait Task<F: Future<Output = Result<(), ()>>> {
+    async fn spawn(&self, f: F) -> Result<(), ()> {
+        f.await
+    }
+}
+
+#[maybe_async::maybe_async(AFIT)]
+impl<F: Future<Output = Result<(), ()>>> Task<F> for Runner {}
+
+#[maybe_async::maybe_async]
+pub async fn read_all<Fut: Future<Output = std::io::Result<Vec<u8>>>>(
+    fut: Fut,
+) -> std::io::Result<Vec<u8>> {
+    fut.await
+}
+
+#[maybe_async::maybe_async]
+pub async fn pair<F>(f: F) -> (u8, &'static str)
+where
+    F: Future<Output = (u8, &'static str)>,
+{
+    f.await
+}
+
+#[maybe_async::maybe_async]
+pub async fn sum_all<F: Future<Output = [u8; 2]>>(futs: Vec<F>) -> F::Output {
+    let mut sum = [0; 2];
+    for f in futs {
+        let [a, b] = f.await;
+        sum = [sum[0] + a, sum[1] + b];
+    }
+    sum
+}
+
+// where predicates on types other than generic parameters are kept as is
+#[maybe_async::maybe_async]
+pub async fn count<'a, T, U, F>(items: &'a T, others: &U, f: F) -> usize
+where
+    &'a T: IntoIterator,
+    [u8]: ToOwned,
+    for<'b> &'b U: IntoIterator,
+    F: Future<Output = usize>,
+{
+    items.into_iter().count() + others.into_iter().count() + f.await
+}
+
+#[maybe_async::maybe_async]
+pub async fn spawn_task(
+    f: impl Future<Output = Vec<u8>>,
+    g: Pin<Box<dyn Future<Output = u8>>>,
+) -> usize {
+    f.await.len() + g.await as usize
+}
+
+#[maybe_async::maybe_async(AFIT)]
+trait Source {
+    type Item;
+
+    async fn next_with<F: Future<Output = Self::Item>>(&self, f: F) -> Self::Item {
+        f.await
+    }
+}
+
+#[maybe_async::maybe_async(AFIT)]
+impl Source for Runner {
+    type Item = u8;
+}
+
 #[maybe_async::sync_impl]
 fn main() {
     with_fn(|| Ok(())).unwrap();
     with_fn_where(|| Ok(())).unwrap();
+    Runner.run(Ok(())).unwrap();
+    Runner.run_where(Ok(())).unwrap();
+    Wrapper(Ok(())).wait().unwrap();
+    Runner.spawn(Ok(())).unwrap();
+    assert_eq!(read_all(Ok(vec![1])).unwrap(), vec![1]);
+    assert_eq!(pair((1, "a")), (1, "a"));
+    assert_eq!(sum_all(vec![[1, 2], [3, 4]]), [4, 6]);
+    assert_eq!(Runner.next_with(1), 1);
+    assert_eq!(count(&vec![1, 2], &[3], 1), 4);
+    assert_eq!(spawn_task(vec![1, 2], 3), 5);
 }
 
 #[maybe_async::async_impl]
@@ -31,4 +145,14 @@ fn main() {
 async fn main() {
     with_fn(|| async { Ok(()) }).await.unwrap();
     with_fn_where(|| async { Ok(()) }).await.unwrap();
+    Runner.run(async { Ok(()) }).await.unwrap();
+    Runner.run_where(async { Ok(()) }).await.unwrap();
+    Wrapper(async { Ok(()) }).wait().await.unwrap();
+    Runner.spawn(async { Ok(()) }).await.unwrap();
+    assert_eq!(read_all(async { Ok(vec![1]) }).await.unwrap(), vec![1]);
+    assert_eq!(pair(async { (1, "a") }).await, (1, "a"));
+    assert_eq!(sum_all(vec![async { [1, 2] }]).await, [1, 2]);
+    assert_eq!(Runner.next_with(async { 1 }).await, 1);
+    assert_eq!(count(&vec![1, 2], &[3], async { 1 }).await, 4);
+    assert_eq!(spawn_task(async { vec![1, 2] }, Box::pin(async { 3 })).await, 5);
 }
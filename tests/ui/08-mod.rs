@@ -0,0 +1,95 @@
+#![allow(dead_code)]
+#![allow(async_fn_in_trait)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+mod client {
+    pub trait Trait {
+        fn sync_fn() {}
+
+        async fn declare_async(&self);
+
+        async fn async_fn(&self) {
+            self.declare_async().await
+        }
+    }
+
+    pub struct Struct;
+
+    impl Struct {
+        pub async fn inherent(&self) -> u32 {
+            async { 1 }.await
+        }
+    }
+
+    impl Trait for Struct {
+        async fn declare_async(&self) {}
+    }
+
+    pub async fn free_fn(s: &Struct) -> u32 {
+        s.async_fn().await;
+        s.inherent().await + nested::nested_fn().await
+    }
+
+    pub mod nested {
+        pub async fn nested_fn() -> u32 {
+            2
+        }
+    }
+
+    #[maybe_async::sync_impl]
+    pub fn only_sync() -> u32 {
+        3
+    }
+
+    #[maybe_async::async_impl]
+    pub async fn only_async() -> u32 {
+        async { 3 }.await
+    }
+}
+
+#[maybe_async(AFIT)]
+mod afit {
+    pub trait AfitTrait {
+        async fn declare_async_afit(&self) -> u32;
+    }
+
+    pub struct AfitStruct;
+
+    impl AfitTrait for AfitStruct {
+        async fn declare_async_afit(&self) -> u32 {
+            4
+        }
+    }
+}
+
+#[maybe_async::must_be_sync]
+mod always_sync {
+    pub async fn always_sync_fn() -> u32 {
+        async { 5 }.await
+    }
+}
+
+#[cfg(feature = "is_sync")]
+fn main() {
+    use afit::AfitTrait;
+
+    let s = client::Struct;
+    assert_eq!(client::free_fn(&s), 3);
+    assert_eq!(client::only_sync(), 3);
+    assert_eq!(afit::AfitStruct.declare_async_afit(), 4);
+    assert_eq!(always_sync::always_sync_fn(), 5);
+}
+
+#[cfg(not(feature = "is_sync"))]
+#[async_std::main]
+async fn main() {
+    use afit::AfitTrait;
+
+    let s = client::Struct;
+    assert_eq!(client::free_fn(&s).await, 3);
+    assert_eq!(client::only_async().await, 3);
+    assert_eq!(afit::AfitStruct.declare_async_afit().await, 4);
+    assert_eq!(always_sync::always_sync_fn(), 5);
+}
@@ -0,0 +1,30 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn double(x: u32) -> u32 {
+    x * 2
+}
+
+#[maybe_async]
+async fn call_closures(base: u32) -> u32 {
+    let add = async |x: u32| double(x).await + base;
+    let offset = 1;
+    let add_offset = async move |x: u32| {
+        let y = double(x).await;
+        y + offset
+    };
+    add(1).await + add_offset(2).await
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(call_closures(10), 17);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(call_closures(10).await, 17);
+}
@@ -0,0 +1,70 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn fallible(fail: bool) -> Result<u32, String> {
+    if fail {
+        Err(String::from("failed"))
+    } else {
+        Ok(1)
+    }
+}
+
+// `?` inside the async block must only leave the block
+#[maybe_async]
+async fn try_in_block(fail: bool) -> u32 {
+    let res: Result<u32, String> = async {
+        let v = fallible(fail).await?;
+        Ok(v + 1)
+    }
+    .await;
+    res.unwrap_or(0)
+}
+
+// `return` inside the async block must only leave the block
+#[maybe_async]
+async fn return_in_block(early: bool) -> u32 {
+    let v = async move {
+        if early {
+            return 1;
+        }
+        2
+    }
+    .await;
+    v + 10
+}
+
+fn parse(fail: bool) -> Result<u32, String> {
+    if fail {
+        Err(String::from("failed"))
+    } else {
+        Ok(3)
+    }
+}
+
+// `?` hidden inside a macro invocation
+#[maybe_async]
+async fn try_in_macro(fail: bool) -> String {
+    let res: Result<String, String> = async { Ok(format!("{}", parse(fail)?)) }.await;
+    res.unwrap_or_default()
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(try_in_block(false), 2);
+    assert_eq!(try_in_block(true), 0);
+    assert_eq!(return_in_block(true), 11);
+    assert_eq!(return_in_block(false), 12);
+    assert_eq!(try_in_macro(true), "");
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(try_in_block(false).await, 2);
+    assert_eq!(try_in_block(true).await, 0);
+    assert_eq!(return_in_block(true).await, 11);
+    assert_eq!(return_in_block(false).await, 12);
+    assert_eq!(try_in_macro(true).await, "");
+}
@@ -0,0 +1,98 @@
+#![allow(dead_code)]
+
+use std::cell::Cell;
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn record(log: &Cell<u32>, digit: u32) -> u32 {
+    log.set(log.get() * 10 + digit);
+    digit
+}
+
+// The async blocks stored in variables must run at the `.await` site.
+#[maybe_async(lazy)]
+async fn deferred(log: &Cell<u32>, retry: bool) -> u32 {
+    let first = async { record(log, 1).await };
+    let second = Box::pin(async move { record(log, 2).await });
+    record(log, 3).await;
+    let mut sum = second.await;
+    if retry {
+        sum += first.await;
+    }
+    // shadowed by a plain value, which must be kept as is
+    let first = async { 4 }.await;
+    sum + first
+}
+
+#[maybe_async]
+async fn retry<F, Fut>(f: F) -> u32
+where
+    F: Fn() -> Fut,
+    Fut: std::future::Future<Output = u32>,
+{
+    f().await
+}
+
+// a typed binding, and one moved into another variable, are deferred as well,
+// and a closure returning an async block runs it when called
+#[maybe_async(lazy)]
+async fn moved_and_typed(log: &Cell<u32>) -> u32 {
+    let typed: std::pin::Pin<Box<dyn std::future::Future<Output = u32> + '_>> =
+        Box::pin(async { record(log, 7).await });
+    let moved = typed;
+    record(log, 8).await;
+    let retried = retry(|| async { record(log, 9).await }).await;
+    moved.await + retried
+}
+
+#[maybe_async::must_be_sync(lazy)]
+fn always_deferred(log: &Cell<u32>) -> u32 {
+    let fut = async { record_sync(log, 5) };
+    record_sync(log, 6);
+    fut.await
+}
+
+fn record_sync(log: &Cell<u32>, digit: u32) -> u32 {
+    log.set(log.get() * 10 + digit);
+    digit
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    let log = Cell::new(0);
+    assert_eq!(deferred(&log, true), 7);
+    assert_eq!(log.get(), 321);
+
+    let log = Cell::new(0);
+    assert_eq!(deferred(&log, false), 6);
+    assert_eq!(log.get(), 32);
+
+    let log = Cell::new(0);
+    assert_eq!(always_deferred(&log), 5);
+    assert_eq!(log.get(), 65);
+
+    let log = Cell::new(0);
+    assert_eq!(moved_and_typed(&log), 16);
+    assert_eq!(log.get(), 897);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    let log = Cell::new(0);
+    assert_eq!(deferred(&log, true).await, 7);
+    assert_eq!(log.get(), 321);
+
+    let log = Cell::new(0);
+    assert_eq!(deferred(&log, false).await, 6);
+    assert_eq!(log.get(), 32);
+
+    let log = Cell::new(0);
+    assert_eq!(always_deferred(&log), 5);
+    assert_eq!(log.get(), 65);
+
+    let log = Cell::new(0);
+    assert_eq!(moved_and_typed(&log).await, 16);
+    assert_eq!(log.get(), 897);
+}
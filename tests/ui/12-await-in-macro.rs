@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn get() -> Result<u32, String> {
+    Ok(1)
+}
+
+#[maybe_async]
+async fn await_in_macros() -> Result<(), String> {
+    assert_eq!(get().await?, 1);
+    assert!(get().await.is_ok(), "get failed: {:?}", get().await);
+    let s = format!("{}-{x}", get().await?, x = get().await?);
+    assert_eq!(s, "1-1");
+    // not a list of expressions, `.await` is removed token-wise
+    let v = vec![get().await?; 2];
+    assert_eq!(v, vec![1, 1]);
+    println!("{}", async { get().await }.await?);
+    Ok(())
+}
+
+#[maybe_async]
+async fn await_in_local_macro() -> u32 {
+    macro_rules! get_value {
+        () => {
+            get().await.unwrap()
+        };
+    }
+    get_value!() + 1
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    await_in_macros().unwrap();
+    assert_eq!(await_in_local_macro(), 2);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    await_in_macros().await.unwrap();
+    assert_eq!(await_in_local_macro().await, 2);
+}
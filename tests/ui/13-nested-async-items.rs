@@ -0,0 +1,59 @@
+#![allow(dead_code)]
+
+use std::future::Future;
+
+use maybe_async::maybe_async;
+
+fn assert_future<F: Future>(_: F) {}
+
+#[maybe_async]
+async fn outer() -> u32 {
+    async fn helper(x: u32) -> u32 {
+        x + 1
+    }
+
+    struct Local;
+
+    impl Local {
+        async fn get(&self) -> u32 {
+            helper(1).await
+        }
+    }
+
+    #[maybe_async::must_be_async]
+    async fn stays_async() -> u32 {
+        async { 5 }.await
+    }
+    assert_future(stays_async());
+
+    Local.get().await + helper(2).await
+}
+
+struct Struct;
+
+#[maybe_async]
+impl Struct {
+    async fn converted(&self) -> u32 {
+        1
+    }
+
+    #[maybe_async::must_be_async]
+    async fn kept_async(&self) -> u32 {
+        async { 2 }.await
+    }
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(outer(), 5);
+    assert_eq!(Struct.converted(), 1);
+    assert_future(Struct.kept_async());
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(outer().await, 5);
+    assert_eq!(Struct.converted().await, 1);
+    assert_eq!(Struct.kept_async().await, 2);
+}
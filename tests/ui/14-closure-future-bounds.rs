@@ -0,0 +1,44 @@
+#![allow(dead_code)]
+
+#[allow(unused_imports)]
+use std::{future::Future, pin::Pin};
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+pub async fn retry<F>(f: F, req: u32) -> u32
+where
+    F: Fn(u32) -> Pin<Box<dyn Future<Output = u32> + Send>>,
+{
+    f(req).await + f(req).await
+}
+
+#[maybe_async]
+pub async fn run_boxed(f: Box<dyn FnOnce() -> Pin<Box<dyn Future<Output = u32>>>>) -> u32 {
+    f().await
+}
+
+#[maybe_async]
+pub async fn run_ptr(f: fn(u32) -> Pin<Box<dyn Future<Output = u32> + Send>>) -> u32 {
+    f(1).await
+}
+
+#[maybe_async]
+fn double(x: u32) -> Pin<Box<dyn Future<Output = u32> + Send>> {
+    Box::pin(async move { x * 2 })
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(retry(|r| r + 1, 1), 4);
+    assert_eq!(run_boxed(Box::new(|| 2)), 2);
+    assert_eq!(run_ptr(double), 2);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(retry(|r| Box::pin(async move { r + 1 }), 1).await, 4);
+    assert_eq!(run_boxed(Box::new(|| Box::pin(async { 2 }))).await, 2);
+    assert_eq!(run_ptr(double).await, 2);
+}
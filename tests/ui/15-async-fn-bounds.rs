@@ -0,0 +1,41 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn with_conn<F>(f: F) -> u32
+where
+    F: AsyncFn(&u32) -> u32,
+{
+    let conn = 1;
+    f(&conn).await
+}
+
+#[maybe_async]
+async fn twice(mut f: impl AsyncFnMut() -> u32) -> u32 {
+    f().await + f().await
+}
+
+#[maybe_async]
+async fn once<F: std::ops::AsyncFnOnce() -> u32>(f: F) -> u32 {
+    f().await
+}
+
+#[maybe_async]
+async fn caller() -> u32 {
+    let base = 1;
+    with_conn(async |conn: &u32| *conn + base).await
+        + twice(async || base).await
+        + once(async move || base).await
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(caller(), 5);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(caller().await, 5);
+}
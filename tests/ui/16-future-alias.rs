@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+
+#[allow(unused_imports)]
+use futures::future::{BoxFuture, FutureExt, LocalBoxFuture};
+
+use maybe_async::maybe_async;
+
+pub type ApiFuture<'a, T> = BoxFuture<'a, T>;
+pub type OtherFuture<'a, T> = LocalBoxFuture<'a, T>;
+
+#[maybe_async(AFIT)]
+pub trait Client: Sync {
+    fn get(&self) -> BoxFuture<'_, u32>;
+
+    fn get_local(&self) -> futures::future::LocalBoxFuture<'_, u32>;
+
+    fn get_twice(&self) -> LocalBoxFuture<'_, u32> {
+        Box::pin(async move { self.get().await + self.get_local().await })
+    }
+}
+
+struct Foo;
+
+#[maybe_async(AFIT)]
+impl Client for Foo {
+    fn get(&self) -> BoxFuture<'_, u32> {
+        Box::pin(async { 1 })
+    }
+
+    fn get_local(&self) -> LocalBoxFuture<'_, u32> {
+        Box::pin(async { 2 })
+    }
+}
+
+#[maybe_async(Send, future_alias = "ApiFuture", future_alias = "OtherFuture")]
+fn fetch(client: &Foo) -> ApiFuture<'_, Result<u32, String>> {
+    Box::pin(async move { Ok(client.get().await) })
+}
+
+#[maybe_async(future_alias = "OtherFuture")]
+fn fetch_other(client: &Foo) -> OtherFuture<'_, u32> {
+    Box::pin(async move { client.get_twice().await })
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(fetch(&Foo), Ok(1));
+    assert_eq!(fetch_other(&Foo), 3);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(fetch(&Foo).await, Ok(1));
+    assert_eq!(fetch_other(&Foo).await, 3);
+}
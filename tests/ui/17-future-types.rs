@@ -0,0 +1,102 @@
+#![allow(dead_code)]
+
+#[allow(unused_imports)]
+use std::{future::Future, pin::Pin};
+
+#[allow(unused_imports)]
+use futures::future::BoxFuture;
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn future_types() -> u8 {
+    // let bindings
+    let f: Pin<Box<dyn Future<Output = u8>>> = Box::pin(async { 1 });
+    let futs: Vec<BoxFuture<'static, u8>> = vec![Box::pin(async { 2 }), Box::pin(async { 3 })];
+
+    // fields
+    struct Pending {
+        fut: Pin<Box<dyn Future<Output = u8>>>,
+    }
+    let pending = Pending {
+        fut: Box::pin(async { 4 }),
+    };
+
+    // casts and turbofish
+    let cast = Box::pin(async { 5u8 }) as Pin<Box<dyn Future<Output = u8>>>;
+    let turbofish = std::convert::identity::<BoxFuture<'static, u8>>(Box::pin(async { 6 }));
+
+    let mut sum = f.await + pending.fut.await + cast.await + turbofish.await;
+    for fut in futs {
+        sum += fut.await;
+    }
+    sum
+}
+
+#[maybe_async]
+async fn take_all(futs: Vec<BoxFuture<'_, u8>>) -> u8 {
+    let mut sum = 0;
+    for fut in futs {
+        sum += fut.await;
+    }
+    sum
+}
+
+// a `Context` of the crate, unlike the one of `std::task`, has a sync
+// equivalent
+pub struct Context<'a> {
+    name: &'a str,
+}
+
+#[maybe_async]
+async fn handle(cx: &Context<'_>) -> usize {
+    cx.name.len()
+}
+
+// a `Poll` and a `select!` of the crate are not those of async code either
+#[derive(Debug, PartialEq)]
+pub enum Poll {
+    Ready,
+    Pending,
+}
+
+macro_rules! select {
+    ($($value:expr),*) => {
+        [$($value),*].into_iter().max().unwrap()
+    };
+}
+
+#[maybe_async]
+async fn poll(ready: bool) -> Poll {
+    if ready {
+        Poll::Ready
+    } else {
+        Poll::Pending
+    }
+}
+
+#[maybe_async]
+async fn pick() -> u8 {
+    select!(take_all(vec![]).await, 3)
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(future_types(), 21);
+    assert_eq!(take_all(vec![1, 2]), 3);
+    assert_eq!(handle(&Context { name: "sync" }), 4);
+    assert_eq!(poll(true), Poll::Ready);
+    assert_eq!(poll(false), Poll::Pending);
+    assert_eq!(pick(), 3);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(future_types().await, 21);
+    assert_eq!(take_all(vec![Box::pin(async { 1 }), Box::pin(async { 2 })]).await, 3);
+    assert_eq!(handle(&Context { name: "async" }).await, 5);
+    assert_eq!(poll(true).await, Poll::Ready);
+    assert_eq!(poll(false).await, Poll::Pending);
+    assert_eq!(pick().await, 3);
+}
@@ -0,0 +1,48 @@
+#![allow(dead_code)]
+
+#[allow(unused_imports)]
+use std::future::Future;
+
+#[allow(unused_imports)]
+use futures::future::FutureExt;
+#[allow(unused_imports)]
+use tracing::Instrument;
+
+use maybe_async::maybe_async;
+
+pub trait RetryExt: Future + Sized {
+    fn retry(self, _times: usize) -> Self {
+        self
+    }
+}
+
+impl<F: Future> RetryExt for F {}
+
+#[maybe_async]
+async fn get(x: u32) -> u32 {
+    x
+}
+
+#[maybe_async(future_adapter = "retry")]
+async fn adapted() -> u32 {
+    let span = tracing::info_span!("adapted");
+    let boxed = get(1).boxed().await;
+    let fused = get(2).fuse().await;
+    let instrumented = get(3).instrument(span).await;
+    let in_span = get(4).in_current_span().boxed().await;
+    let pinned = Box::pin(get(5)).await;
+    let retried = get(6).retry(3).await;
+    let block = async { get(7).await }.boxed().await;
+    boxed + fused + instrumented + in_span + pinned + retried + block
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(adapted(), 28);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(adapted().await, 28);
+}
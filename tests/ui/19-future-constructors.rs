@@ -0,0 +1,82 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn constructors() -> Result<u32, String> {
+    #[allow(unused_imports)]
+    use futures::future::{err, ok, ready};
+
+    let a = std::future::ready(1).await;
+    let b = ready(2).await;
+    let c = futures::future::ok::<u32, String>(3).await?;
+    let d: Result<u32, String> = err("e".to_string()).await;
+    let e = ok::<u32, String>(4).await?;
+    let f = std::pin::pin!(async { 5 }).await;
+    Ok(a + b + c + d.unwrap_or(0) + e + f)
+}
+
+#[maybe_async]
+fn blocking() -> u32 {
+    let v = futures::executor::block_on(async { 6 });
+    v + futures::executor::block_on(std::future::ready(1))
+}
+
+// functions of the crate named like future constructors are left alone
+mod local {
+    use maybe_async::maybe_async;
+
+    #[derive(Debug, PartialEq)]
+    pub struct MyError(String);
+
+    fn ready(x: u32) -> bool {
+        x > 3
+    }
+
+    fn err(msg: &str) -> MyError {
+        MyError(msg.to_string())
+    }
+
+    #[maybe_async]
+    pub async fn check(x: u32) -> Result<u32, MyError> {
+        if ready(x) {
+            Ok(x)
+        } else {
+            Err(err("not ready"))
+        }
+    }
+
+    macro_rules! pin {
+        ($value:expr) => {
+            ($value, $value)
+        };
+    }
+
+    #[maybe_async]
+    pub async fn pair(x: u32) -> (u32, u32) {
+        pin!(x)
+    }
+
+    pub fn not_ready() -> MyError {
+        err("not ready")
+    }
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(constructors(), Ok(15));
+    assert_eq!(blocking(), 7);
+    assert_eq!(local::check(5), Ok(5));
+    assert_eq!(local::check(1), Err(local::not_ready()));
+    assert_eq!(local::pair(2), (2, 2));
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(constructors().await, Ok(15));
+    assert_eq!(blocking(), 7);
+    assert_eq!(local::check(5).await, Ok(5));
+    assert_eq!(local::check(1).await, Err(local::not_ready()));
+    assert_eq!(local::pair(2).await, (2, 2));
+}
@@ -0,0 +1,80 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn get(x: u32) -> u32 {
+    x
+}
+
+#[maybe_async]
+async fn check(x: u32) -> Result<u32, String> {
+    if x < 10 {
+        Ok(x)
+    } else {
+        Err(format!("{x} is too big"))
+    }
+}
+
+#[maybe_async]
+async fn joined() -> Result<u32, String> {
+    let (a, b) = futures::join!(get(1), get(2));
+    let (c,) = tokio::join!(get(3));
+    let (d, e) = futures::try_join!(check(4), check(5))?;
+    let (f, g) = tokio::try_join!(check(6), async { check(7).await })?;
+    tokio::join!(get(0), get(0));
+    Ok(a + b + c + d + e + f + g)
+}
+
+#[maybe_async]
+async fn join_failed() -> Result<(u32, u32), String> {
+    futures::try_join!(check(20), check(1))
+}
+
+#[maybe_async]
+async fn all() -> Result<u32, String> {
+    let values = futures::future::join_all((1..4).map(get)).await;
+    let checked = futures::future::try_join_all(vec![check(4), check(5)]).await?;
+    let failed = futures::future::try_join_all((8..12).map(check)).await;
+    assert_eq!(failed, Err("10 is too big".to_string()));
+    Ok(values.iter().sum::<u32>() + checked.iter().sum::<u32>())
+}
+
+// functions and macros of the crate named like joins are left alone
+mod thread_pool {
+    pub fn join_all(values: Vec<u32>) -> u32 {
+        values.iter().product()
+    }
+}
+
+macro_rules! join {
+    ($a:expr, $b:expr) => {
+        $a * 10 + $b
+    };
+}
+
+#[maybe_async]
+async fn local() -> u32 {
+    #[allow(unused_imports)]
+    use futures::future::join_all;
+
+    let values = join_all(vec![get(2), get(3)]).await;
+    join!(thread_pool::join_all(values), 4)
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(joined(), Ok(28));
+    assert_eq!(join_failed(), Err("20 is too big".to_string()));
+    assert_eq!(all(), Ok(15));
+    assert_eq!(local(), 64);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(joined().await, Ok(28));
+    assert_eq!(join_failed().await, Err("20 is too big".to_string()));
+    assert_eq!(all().await, Ok(15));
+    assert_eq!(local().await, 64);
+}
@@ -0,0 +1,67 @@
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+async fn get(x: u32) -> u32 {
+    x
+}
+
+fn blocking() -> u32 {
+    4
+}
+
+#[maybe_async(spawn_thread)]
+async fn tokio_tasks() -> u32 {
+    let x = 1;
+    let handle = tokio::spawn(async move { get(x).await });
+    let direct = tokio::spawn(get(2)).await.unwrap();
+    let task = tokio::task::spawn(async move {
+        if x > 10 {
+            return 0;
+        }
+        get(3).await
+    });
+    let from_blocking = tokio::task::spawn_blocking(blocking).await.unwrap();
+    let closure = tokio::task::spawn_blocking(|| 5).await.unwrap();
+    let blocking_handle = tokio::task::spawn_blocking(move || x + 5);
+    handle.await.unwrap()
+        + direct
+        + task.await.unwrap()
+        + from_blocking
+        + closure
+        + blocking_handle.await.unwrap()
+}
+
+#[maybe_async(spawn_thread)]
+async fn async_std_tasks() -> u32 {
+    let handle = async_std::task::spawn(async { get(1).await });
+    let blocking = async_std::task::spawn_blocking(|| 2).await;
+    handle.await + blocking
+}
+
+#[maybe_async(spawn_thread)]
+async fn imported_tasks() -> u32 {
+    #[allow(unused_imports)]
+    use async_std::task::spawn_blocking;
+    #[allow(unused_imports)]
+    use tokio::spawn;
+
+    let handle = spawn(async { get(1).await });
+    handle.await.unwrap() + spawn_blocking(|| 2).await
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(tokio_tasks(), 21);
+    assert_eq!(async_std_tasks(), 3);
+    assert_eq!(imported_tasks(), 3);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(tokio_tasks().await, 21);
+    assert_eq!(async_std_tasks().await, 3);
+    assert_eq!(imported_tasks().await, 3);
+}
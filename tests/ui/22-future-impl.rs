@@ -0,0 +1,106 @@
+#![allow(dead_code)]
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use maybe_async::maybe_async;
+
+pub struct Response(u32);
+
+#[maybe_async]
+impl Future for Response {
+    type Output = u32;
+
+    fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+        Poll::Ready(self.0)
+    }
+}
+
+pub struct Countdown<T> {
+    left: u32,
+    value: T,
+}
+
+#[maybe_async(wait)]
+impl<T: Clone + Unpin> Future for Countdown<T> {
+    type Output = T;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        if self.left == 0 {
+            return Poll::Ready(self.value.clone());
+        }
+        self.left -= 1;
+        cx.waker().wake_by_ref();
+        Poll::Pending
+    }
+}
+
+pub struct Request(u32);
+
+#[maybe_async(wait)]
+impl IntoFuture for Request {
+    type Output = u32;
+    type IntoFuture = std::future::Ready<u32>;
+
+    fn into_future(self) -> Self::IntoFuture {
+        std::future::ready(self.0)
+    }
+}
+
+#[maybe_async]
+mod client {
+    pub struct Fetch;
+
+    impl std::future::Future for Fetch {
+        type Output = u32;
+
+        fn poll(
+            self: std::pin::Pin<&mut Self>,
+            _cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<u32> {
+            std::task::Poll::Ready(4)
+        }
+    }
+
+    pub async fn fetch() -> u32 {
+        5
+    }
+}
+
+// the imports of a future kept by `wait` are kept as well
+#[maybe_async(wait)]
+mod timer {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    pub struct Tick(pub u32);
+
+    impl Future for Tick {
+        type Output = u32;
+
+        fn poll(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<u32> {
+            Poll::Ready(self.0)
+        }
+    }
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(Countdown { left: 3, value: 2 }.wait(), 2);
+    assert_eq!(Request(3).wait(), 3);
+    assert_eq!(client::fetch(), 5);
+    assert_eq!(timer::Tick(6).wait(), 6);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(Response(1).await, 1);
+    assert_eq!(Countdown { left: 3, value: 2 }.await, 2);
+    assert_eq!(Request(3).await, 3);
+    assert_eq!(client::Fetch.await, 4);
+    assert_eq!(client::fetch().await, 5);
+    assert_eq!(timer::Tick(6).await, 6);
+}
@@ -0,0 +1,101 @@
+#![allow(dead_code)]
+
+#[allow(unused_imports)]
+use std::{future::Future, pin::Pin};
+
+#[allow(unused_imports)]
+use futures::future::BoxFuture;
+
+use maybe_async::maybe_async;
+
+pub struct Conn(u32);
+
+#[maybe_async]
+pub struct Pool {
+    pending: Vec<Pin<Box<dyn Future<Output = Conn> + Send>>>,
+    size: usize,
+}
+
+#[maybe_async]
+pub enum Slot {
+    Ready(Conn),
+    Pending(Pin<Box<dyn Future<Output = Conn> + Send>>),
+}
+
+#[maybe_async]
+pub struct Wrapper<F: Future<Output = u32>>(F);
+
+#[maybe_async]
+impl<F: Future<Output = u32>> Wrapper<F> {
+    pub async fn get(self) -> u32 {
+        self.0.await
+    }
+}
+
+#[maybe_async]
+pub enum Task<F>
+where
+    F: Future<Output = u32>,
+{
+    Done(u32),
+    Running(F),
+}
+
+#[maybe_async]
+impl<F> Task<F>
+where
+    F: Future<Output = u32>,
+{
+    pub async fn get(self) -> u32 {
+        match self {
+            Task::Done(value) => value,
+            Task::Running(fut) => fut.await,
+        }
+    }
+}
+
+#[maybe_async]
+pub type ApiResult<'a, T> = BoxFuture<'a, Result<T, String>>;
+
+#[maybe_async]
+async fn connect(id: u32) -> Conn {
+    Conn(id)
+}
+
+#[maybe_async]
+fn fetch<'a>(id: u32) -> ApiResult<'a, u32> {
+    Box::pin(async move { Ok(id) })
+}
+
+#[maybe_async]
+async fn run() -> u32 {
+    let pool = Pool {
+        pending: vec![
+            Box::pin(async { connect(1).await }),
+            Box::pin(async { connect(2).await }),
+        ],
+        size: 2,
+    };
+    let mut total = 0;
+    for conn in pool.pending {
+        total += conn.await.0;
+    }
+    let slot = Slot::Pending(Box::pin(async { connect(3).await }));
+    if let Slot::Pending(conn) = slot {
+        total += conn.await.0;
+    }
+    total += Wrapper(async { connect(5).await.0 }).get().await;
+    total += Task::Running(async { connect(6).await.0 }).get().await;
+    total + fetch(4).await.unwrap()
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(run(), 21);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(run().await, 21);
+}
@@ -0,0 +1,96 @@
+#![deny(unused_imports)]
+#![allow(dead_code)]
+
+use maybe_async::maybe_async;
+
+#[maybe_async]
+use std::{future::Future, pin::Pin};
+
+#[maybe_async]
+use futures::future::{BoxFuture, FutureExt as _};
+
+#[maybe_async]
+use tokio::io::{self, AsyncReadExt};
+
+#[maybe_async]
+use tokio::{
+    fs,
+    time::{sleep as delay, Duration},
+};
+
+// types of the crate named like async imports are still imported
+mod shop {
+    pub mod model {
+        pub struct Pin(pub u32);
+    }
+
+    pub mod market {
+        pub struct Future(pub u32);
+    }
+
+    #[maybe_async::maybe_async]
+    use crate::shop::{market::Future, model::Pin};
+
+    #[maybe_async::maybe_async]
+    pub async fn total() -> u32 {
+        Pin(1).0 + Future(2).0
+    }
+}
+
+// async imports still referred to by sync code are kept
+#[maybe_async]
+mod still_used {
+    use std::future::Future;
+    use std::pin::Pin;
+
+    pub fn first(v: Pin<&mut Vec<u8>>) -> u8 {
+        v[0]
+    }
+
+    #[maybe_async::must_be_async]
+    pub async fn run(f: impl Future<Output = u8>) -> u8 {
+        f.await
+    }
+}
+
+#[maybe_async]
+async fn get(x: u32) -> u32 {
+    x
+}
+
+#[maybe_async]
+fn pinned(x: u32) -> Pin<Box<dyn Future<Output = u32> + Send>> {
+    Box::pin(async move { get(x).boxed().await })
+}
+
+#[maybe_async]
+fn aliased(x: u32) -> BoxFuture<'static, u32> {
+    Box::pin(async move { x })
+}
+
+#[maybe_async]
+async fn read() -> io::Result<u32> {
+    let mut buf = [0u8; 3];
+    let mut input: &[u8] = b"abc";
+    let n = input.read(&mut buf).await?;
+    delay(Duration::from_millis(1)).await;
+    let is_dir = fs::metadata(".").await?.is_dir();
+    Ok(n as u32 + is_dir as u32 + pinned(1).await + aliased(2).await)
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(read().unwrap(), 7);
+    assert_eq!(shop::total(), 3);
+    assert_eq!(still_used::first(std::pin::Pin::new(&mut vec![1])), 1);
+    assert_eq!(futures::executor::block_on(still_used::run(async { 2 })), 2);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(read().await.unwrap(), 7);
+    assert_eq!(shop::total().await, 3);
+    assert_eq!(still_used::first(std::pin::Pin::new(&mut vec![1])), 1);
+    assert_eq!(futures::executor::block_on(still_used::run(async { 2 })), 2);
+}
@@ -0,0 +1,86 @@
+#![allow(dead_code)]
+
+maybe_async::maybe_async_items! {
+    use std::collections::HashMap;
+
+    const BASE: u32 = 10;
+
+    macro_rules! twice {
+        ($fut:expr) => {
+            $fut.await.unwrap_or(0) + $fut.await.unwrap_or(0)
+        };
+    }
+
+    pub trait Store {
+        async fn get(&self, key: &str) -> Option<u32>;
+    }
+
+    pub struct MemStore(HashMap<String, u32>);
+
+    impl Store for MemStore {
+        async fn get(&self, key: &str) -> Option<u32> {
+            self.0.get(key).copied()
+        }
+    }
+
+    pub async fn total(store: &MemStore) -> u32 {
+        BASE + twice!(store.get("a"))
+    }
+}
+
+maybe_async::maybe_async_items! {
+    (AFIT, lazy)
+
+    pub trait Counter {
+        async fn count(&self) -> u32;
+    }
+
+    impl Counter for u32 {
+        async fn count(&self) -> u32 {
+            let fut = async { *self };
+            fut.await
+        }
+    }
+}
+
+maybe_async::must_be_sync_items! {
+    pub async fn always_sync() -> u32 {
+        async { 1 }.await
+    }
+
+    pub const ONE: u32 = 1;
+}
+
+maybe_async::must_be_async_items! {
+    (?Send)
+
+    pub trait LocalStore {
+        async fn local(&self) -> u32;
+    }
+
+    impl LocalStore for () {
+        async fn local(&self) -> u32 {
+            2
+        }
+    }
+}
+
+fn store() -> MemStore {
+    MemStore([("a".to_string(), 3)].into_iter().collect())
+}
+
+#[maybe_async::sync_impl]
+fn main() {
+    assert_eq!(total(&store()), 16);
+    assert_eq!(5.count(), 5);
+    assert_eq!(always_sync(), ONE);
+}
+
+#[maybe_async::async_impl]
+#[tokio::main]
+async fn main() {
+    assert_eq!(total(&store()).await, 16);
+    assert_eq!(5.count().await, 5);
+    assert_eq!(always_sync(), ONE);
+    assert_eq!(().local().await, 2);
+}
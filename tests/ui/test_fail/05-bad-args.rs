@@ -0,0 +1,13 @@
+use maybe_async::maybe_async;
+
+// only one async trait mode is allowed
+#[maybe_async(Send, AFIT)]
+async fn two_modes() {}
+
+// unknown option
+#[maybe_async(?Send, eager)]
+async fn unknown_option() {}
+
+fn main() {
+
+}
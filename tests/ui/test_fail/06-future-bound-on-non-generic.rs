@@ -0,0 +1,14 @@
+use std::future::Future;
+
+// a `Future` bound on a reference cannot be erased
+#[maybe_async::must_be_sync]
+async fn by_ref<'a, F>(_f: &'a F) -> u8
+where
+    &'a F: Future<Output = u8>,
+{
+    0
+}
+
+fn main() {
+
+}
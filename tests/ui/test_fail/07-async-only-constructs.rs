@@ -0,0 +1,30 @@
+#![allow(unused_imports)]
+
+use std::pin::Pin;
+use std::task::{self, Poll};
+
+#[maybe_async::must_be_sync]
+async fn select_first() -> u8 {
+    tokio::select! {
+        v = async { 1 } => v,
+        v = async { 2 } => v,
+    }
+}
+
+#[maybe_async::must_be_sync]
+async fn poll_once() -> u8 {
+    std::future::poll_fn(|_cx| Poll::Ready(1)).await
+}
+
+struct Ready;
+
+#[maybe_async::must_be_sync]
+impl Ready {
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> task::Poll<u8> {
+        task::Poll::Pending
+    }
+}
+
+fn main() {
+
+}
@@ -0,0 +1,14 @@
+// an unsupported item lists the supported ones
+#[maybe_async::maybe_async]
+const ANSWER: u32 = 42;
+
+// a syntax error is reported at its span by the parser of the item
+#[maybe_async::maybe_async]
+async fn syntax_error() -> u32 {
+    let x = ;
+    x
+}
+
+fn main() {
+
+}
@@ -0,0 +1,31 @@
+struct Holder {
+    value: u32,
+}
+
+fn consume(value: u32) -> u32 {
+    value
+}
+
+#[maybe_async::must_be_sync(lazy)]
+async fn not_deferred(flag: bool) -> u32 {
+    let mut values = Vec::new();
+    values.push(async { 1 });
+    let awaited = if flag { async { 2 } } else { async { 3 } }.await;
+    let chosen = if flag { async { 2 } } else { async { 3 } };
+    let holder = Holder {
+        value: async { 4 },
+    };
+    let fut = async { 5 };
+    let stored = vec![fut];
+    consume(async { 6 }) + awaited + chosen.await + holder.value + stored.len() as u32
+}
+
+#[maybe_async::must_be_sync(lazy)]
+async fn typed() -> u32 {
+    let fut: u32 = async { 7 };
+    fut.await
+}
+
+fn main() {
+
+}